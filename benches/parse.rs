@@ -0,0 +1,66 @@
+use args::collection::Collection;
+use args::schema::argument::ArgumentType;
+use args::schema::entry::Entry;
+use args::schema::Schema;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+
+fn schema(size: usize) -> Schema {
+    Schema::from_entries(
+        (0..size)
+            .map(|i| match i % 3 {
+                0 => Entry::new(&format!("flag-{i}"), ArgumentType::Bool),
+                1 => Entry::new(&format!("count-{i}"), ArgumentType::Int),
+                _ => Entry::new(&format!("name-{i}"), ArgumentType::Str),
+            })
+            .collect(),
+    )
+}
+
+fn args(size: usize, count: usize) -> Vec<String> {
+    let mut args = vec!["app_name".to_string()];
+    for i in (0..count).map(|i| (i * 7) % size) {
+        match i % 3 {
+            0 => args.push(format!("--flag-{i}")),
+            1 => args.extend([format!("--count-{i}"), i.to_string()]),
+            _ => args.extend([format!("--name-{i}"), format!("value-{i}")]),
+        }
+    }
+    args
+}
+
+fn bench_from_args(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_args");
+    for size in [10, 100, 1000] {
+        let schema = schema(size);
+        let args = args(size, 1000);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                let args = args.iter().map(String::as_str).collect();
+                Collection::from_args(black_box(args), schema.clone())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+    for size in [10, 100, 1000] {
+        let schema = schema(size);
+        let args = args(size, 100);
+        let collection = Collection::from_args(args.iter().map(String::as_str).collect(), schema);
+        let keys: Vec<String> = (0..size).filter(|i| i % 3 == 1).map(|i| format!("count-{i}")).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                keys.iter()
+                    .filter_map(|key| collection.get_int(black_box(key)))
+                    .sum::<i64>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_from_args, bench_lookup);
+criterion_main!(benches);
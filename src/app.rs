@@ -1,20 +1,46 @@
 use crate::collection::Collection;
+use crate::completion::dynamic;
+use crate::doc::markdown;
+use crate::env::Env;
+use crate::error::Error;
+use crate::schema::argument::ArgumentType;
+use crate::schema::entry::Entry;
 use crate::schema::Schema;
+use std::ffi::OsString;
 
 pub struct App {
     collection: Collection,
 }
 
 impl App {
-    pub fn new(args: Vec<String>) -> Self {
-        Self {
-            collection: collection_from_args(args.iter().map(|x| x.as_str()).collect()),
-        }
+    pub fn new(args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        Self::try_new(args).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(args: impl IntoIterator<Item = impl Into<OsString>>) -> Result<Self, Error> {
+        Ok(Self {
+            collection: collection_from_args(args)?,
+        })
+    }
+
+    /// Candidates or a registration script when invoked for shell
+    /// completion, see `completion::dynamic::respond`.
+    pub fn complete(args: &[String], env: &dyn Env) -> Option<String> {
+        dynamic::respond(&get_schema(), args, env)
+    }
+
+    pub fn markdown() -> String {
+        markdown::render(&get_schema())
     }
 
     pub fn run(&self) {
         let col = self.get_collection();
 
+        if col.get_bool("markdown") {
+            print!("{}", Self::markdown());
+            return;
+        }
+
         let string = col.get_str("s").unwrap_or("");
         let int = col.get_int("i").unwrap_or(0);
         let b = col.get_bool("b");
@@ -29,16 +55,20 @@ impl App {
     }
 }
 
-fn collection_from_args(args: Vec<&str>) -> Collection {
-    Collection::from_args(args, get_schema())
+fn collection_from_args(args: impl IntoIterator<Item = impl Into<OsString>>) -> Result<Collection, Error> {
+    Collection::from_args_os(args, get_schema())
 }
 
 fn get_schema() -> Schema {
-    Schema::from(vec![
-        ("s".to_string(), "string".to_string()),
-        ("b".to_string(), "bool".to_string()),
-        ("i".to_string(), "int".to_string()),
-    ])
+    Schema::builder()
+        .name("args")
+        .description("Prints the values of its arguments")
+        .argument("s", "string")
+        .argument("b", "bool")
+        .argument("i", "int")
+        .entry(Entry::new("markdown", ArgumentType::Bool).with_help("Print this reference as Markdown"))
+        .build()
+        .expect("Expects valid schema")
 }
 
 #[cfg(test)]
@@ -116,4 +146,39 @@ mod tests {
         // then
         assert_eq!(app.collection.get_int("i"), Some(-42))
     }
+
+    #[test]
+    fn should_return_error_for_invalid_int() {
+        // given
+        let args = vec!["app_name".to_string(), "-i".to_string(), "x".to_string()];
+
+        // when
+        let result = App::try_new(args);
+
+        // then
+        assert_eq!(result.err().map(|err| err.to_string()), Some("Invalid int for i: x".to_string()));
+    }
+
+    #[test]
+    fn should_complete_when_requested() {
+        // given
+        let args = ["app_name", "--", "app_name", "-"].map(String::from);
+        let env = vec![("COMPLETE", "fish")];
+
+        // when
+        let candidates = App::complete(&args, &env);
+
+        // then
+        assert_eq!(candidates, Some("-s\n-b\n-i\n--markdown\n".to_string()));
+    }
+
+    #[test]
+    fn should_document_its_options_as_markdown() {
+        // when
+        let document = App::markdown();
+
+        // then
+        assert!(document.starts_with("# args\n\nPrints the values of its arguments\n"), "{document}");
+        assert!(document.contains("| `--markdown` | bool |  |  | Print this reference as Markdown |"), "{document}");
+    }
 }
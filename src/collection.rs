@@ -1,9 +1,27 @@
+use crate::collection::builder::CollectionBuilder;
+use crate::collection::config::Config;
+use crate::collection::source::{Origin, Source};
+use crate::env::{posixly_correct, Env, ProcessEnv};
+use crate::error::Error;
+use crate::input::{Input, NoInput, StdInput};
 use crate::token::parser::TokenParser;
 use crate::token::tokens::Tokens;
-use crate::token::Token::{AppName, Argument, StrValue, IntValue};
-use crate::schema::argument::ArgumentType::{self, Bool, Int, Str};
+use crate::token::Span;
+use crate::token::Token::{self, AppName, Argument, Subcommand, StrValue, IntValue, OsStrValue, PathValue, Sensitive, Unknown, Positional};
+use crate::schema::argument::ArgumentType::{self, Bool};
+use crate::schema::duplicates::Duplicates;
+use crate::schema::entry::Entry;
+use crate::schema::value::Value;
 use crate::schema::Schema;
-use std::collections::HashMap;
+use crate::suggest::suggest;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+pub mod builder;
+pub mod config;
+pub mod source;
 
 #[derive(Default)]
 pub struct Collection {
@@ -11,12 +29,45 @@ pub struct Collection {
     ints: HashMap<String,i64>,
     strings: HashMap<String,String>,
     bools: HashMap<String,bool>,
+    os_strings: HashMap<String,OsString>,
+    paths: HashMap<String,PathBuf>,
+    origins: HashMap<String,Origin>,
+    spellings: HashMap<String,String>,
+    subcommand: Option<(String, Box<Collection>)>,
+    unknown: Vec<OsString>,
+    positionals: Vec<OsString>,
 }
 
 impl Collection {
-    fn from(mut tokens: Tokens) -> Collection {
+    fn from(tokens: Tokens) -> Collection {
+        Self::from_with_env(tokens, &ProcessEnv)
+    }
+
+    fn from_with_env(tokens: Tokens, env: &dyn Env) -> Collection {
+        Self::from_sources(tokens, env, None, &StdInput).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn from_sources(
+        mut tokens: Tokens,
+        env: &dyn Env,
+        config: Option<&Config>,
+        input: &dyn Input,
+    ) -> Result<Collection, Error> {
+        let schema = tokens.schema_take().expect("Schema expected");
+        Self::from_schema_tokens(schema, &mut tokens, env, config, input)
+    }
+
+    // Consumes tokens up to the end or the first subcommand, whose own
+    // collection is built from the remaining tokens.
+    fn from_schema_tokens(
+        schema: Schema,
+        tokens: &mut Tokens,
+        env: &dyn Env,
+        config: Option<&Config>,
+        input: &dyn Input,
+    ) -> Result<Collection, Error> {
         let mut collection = Collection {
-            schema: (*tokens.schema().expect("Schema expected")).clone(),
+            schema,
             ..Default::default()
         };
 
@@ -30,58 +81,92 @@ impl Collection {
 
             match (*current.unwrap()).clone() {
                 AppName => (),
+                Argument(Bool, name) => {
+                    let spelling = tokens.current_spelling().map(str::to_string);
+                    let origin = args_origin(tokens.current_span());
+                    collection.insert_arg(name, Value::Bool(true), origin, spelling)?;
+                },
                 Argument(arg_type, name) => {
-                    match arg_type {
-                        Bool => {
-                            collection.bools.insert(name.to_string(), true);
-                        },
-                        Int => {
-                            tokens.next();
-                            current = tokens.current();
-                            if current.is_none() {
-                                panic!("Unexpected end of tokens. Expected Integer");
-                            }
-                            let int_val = match current.unwrap() {
-                                IntValue(val) => val,
-                                AppName => panic!("Unexpected AppName Token! Integer expected."),
-                                StrValue(val) => panic!("Unexpected String Token: {val}! Integer expected."),
-                                Argument(_,name) => panic!("Unexpected Argument Token: {name}! Integer expected."),
-                            };
-                            collection.ints.insert(name.to_string(), *int_val);
-                        },
-                        Str => {
-                            tokens.next();
-                            current = tokens.current();
-                            if current.is_none() {
-                                panic!("Unexpected end of tokens. Expected String");
-                            }
-                            let str_val = match current.unwrap() {
-                                StrValue(val) => val,
-                                AppName => panic!("Unexpected AppName Token! String expected."),
-                                IntValue(val) => panic!("Unexpected Int Token: {val}! String expected."),
-                                Argument(_,name) => panic!("Unexpected Argument Token: {name}! String expected."),
-                            };
-                            collection.strings.insert(name.to_string(), str_val.to_string());
-                        },
-                    }
+                    let spelling = tokens.current_spelling().map(str::to_string);
+                    tokens.next();
+                    let value = match tokens.current() {
+                        Some(token) => token_value(token)
+                            .filter(|value| value.arg_type() == arg_type)
+                            .unwrap_or_else(|| panic!("Unexpected Token: {token:?}! {} expected.", arg_type.name())),
+                        None => return Err(Error::MissingValue(name)),
+                    };
+                    let origin = args_origin(tokens.current_span());
+                    collection.insert_arg(name, value, origin, spelling)?;
+                },
+                Unknown(arg) => collection.unknown.push(arg),
+                Positional(arg) => collection.positionals.push(arg),
+                Subcommand(name) => {
+                    let schema = collection.schema.subcommand(&name).expect("Subcommand defined").clone();
+                    tokens.next();
+                    let subcommand = Self::from_schema_tokens(schema, tokens, env, None, input)?;
+                    collection.subcommand = Some((name, Box::new(subcommand)));
+                    break;
                 },
-                StrValue(val) => panic!("Unexpected String Token: {val}"),
-                IntValue(val) => panic!("Unexpected Int Token: {val}"),
+                token => panic!("Unexpected Token: {token:?}"),
             }
 
             tokens.next();
         }
 
-        collection
+        collection.read_env(env)?;
+        if let Some(config) = config {
+            collection.read_config(config)?;
+        }
+        collection.read_inputs(input)?;
+        collection.check_values()?;
+
+        for entry in collection.schema.entries() {
+            if entry.is_required() && entry.default().is_none() && !collection.contains(entry.name()) {
+                return Err(Error::MissingRequired(entry.name().to_string()));
+            }
+        }
+
+        collection.check_relations()?;
+        collection.check_choices()?;
+        collection.check_paths()?;
+
+        Ok(collection)
+    }
+
+    pub fn from_args_os(args: impl IntoIterator<Item = impl Into<OsString>>, schema: Schema) -> Result<Self, Error> {
+        let tokens = TokenParser::new()
+            .args_os(args)
+            .schema(schema)
+            .posixly_correct(posixly_correct(&ProcessEnv))
+            .try_collect()?;
+
+        Self::from_sources(tokens, &ProcessEnv, None, &StdInput)
+    }
+
+    /// Collects from a command line typed by a user, such as a chat message,
+    /// without consulting the process environment, stdin or files. The
+    /// builder's `command_line` step lets callers choose the sources instead.
+    pub fn from_command_line(command_line: &str, schema: Schema) -> Result<Self, Error> {
+        let env: Vec<(&str, &str)> = Vec::new();
+
+        Self::builder()
+            .schema(schema)
+            .env(&env)
+            .input(&NoInput)
+            .command_line(command_line)
+            .build()
+    }
+
+    pub fn builder<'a>() -> CollectionBuilder<'a> {
+        CollectionBuilder::new()
     }
 
     pub fn from_args(args: Vec<&str>, schema: Schema) -> Self {
-        let parser = TokenParser::new()
-            .args(args)
-            .schema(schema);
-        let tokens = parser.collect();
+        Self::from(tokens_from_args(args, schema, &ProcessEnv))
+    }
 
-        Self::from(tokens)
+    pub fn from_args_with_env(args: Vec<&str>, schema: Schema, env: &dyn Env) -> Self {
+        Self::from_with_env(tokens_from_args(args, schema, env), env)
     }
 
     pub fn get_int(&self, key: &str) -> Option<i64> {
@@ -89,7 +174,10 @@ impl Collection {
             return self.ints.get(key).copied();
         }
         if argument_type_matches(key, &self.schema, ArgumentType::Int) {
-            return None;
+            return match default_value(key, &self.schema) {
+                Some(Value::Int(val)) => Some(*val),
+                _ => None,
+            };
         }
         panic!("Key not found in schema!");
     }
@@ -99,7 +187,10 @@ impl Collection {
             return self.strings.get(key).map(|x| x.as_str());
         }
         if argument_type_matches(key, &self.schema, ArgumentType::Str) {
-            return None;
+            return match default_value(key, &self.schema) {
+                Some(Value::Str(val)) => Some(val.as_str()),
+                _ => None,
+            };
         }
         panic!("Key not found in schema!");
     }
@@ -109,24 +200,465 @@ impl Collection {
             return *self.bools.get(key).unwrap();
         }
         if argument_type_matches(key, &self.schema, ArgumentType::Bool) {
-            return false;
+            return matches!(default_value(key, &self.schema), Some(Value::Bool(true)));
+        }
+        panic!("Key not found in schema!");
+    }
+
+    pub fn get_os_str(&self, key: &str) -> Option<&OsStr> {
+        if self.os_strings.contains_key(key) {
+            return self.os_strings.get(key).map(|x| x.as_os_str());
+        }
+        if argument_type_matches(key, &self.schema, ArgumentType::OsStr) {
+            return match default_value(key, &self.schema) {
+                Some(Value::OsStr(val)) => Some(val.as_os_str()),
+                _ => None,
+            };
         }
         panic!("Key not found in schema!");
     }
+
+    pub fn get_path(&self, key: &str) -> Option<&Path> {
+        if self.paths.contains_key(key) {
+            return self.paths.get(key).map(|x| x.as_path());
+        }
+        if argument_type_matches(key, &self.schema, ArgumentType::Path) {
+            return match default_value(key, &self.schema) {
+                Some(Value::Path(val)) => Some(val.as_path()),
+                _ => None,
+            };
+        }
+        panic!("Key not found in schema!");
+    }
+
+    pub fn subcommand(&self) -> Option<(&str, &Collection)> {
+        self.subcommand
+            .as_ref()
+            .map(|(name, collection)| (name.as_str(), collection.as_ref()))
+    }
+
+    /// Options not defined in the schema, with the values they seem to
+    /// take, in the order they were given.
+    pub fn unknown(&self) -> &[OsString] {
+        &self.unknown
+    }
+
+    pub fn positionals(&self) -> &[OsString] {
+        &self.positionals
+    }
+
+    pub fn source(&self, key: &str) -> Option<Source> {
+        self.origin(key).map(|origin| origin.source())
+    }
+
+    pub fn origin(&self, key: &str) -> Option<Origin> {
+        if let Some(origin) = self.origins.get(key) {
+            return Some(origin.clone());
+        }
+        default_value(key, &self.schema).map(|_| Origin::Default)
+    }
+
+    fn read_env(&mut self, env: &dyn Env) -> Result<(), Error> {
+        let mut values = Vec::new();
+
+        for entry in self.schema.entries() {
+            if self.contains(entry.name()) {
+                continue;
+            }
+            let Some(var) = self.schema.env_var(entry) else {
+                continue;
+            };
+            let Some(raw) = env.var(&var) else {
+                continue;
+            };
+            let value = Value::parse(entry.arg_type(), &raw).ok_or_else(|| redact(entry, Error::InvalidEnv {
+                var: var.clone(),
+                arg_type: entry.arg_type().clone(),
+                value: raw.clone(),
+            }))?;
+            values.push((entry.name().to_string(), value, Origin::Env(var)));
+        }
+
+        for (name, value, origin) in values {
+            self.insert(name, value, origin);
+        }
+
+        Ok(())
+    }
+
+    fn read_config(&mut self, config: &Config) -> Result<(), Error> {
+        for config_value in config.values() {
+            let Some(entry) = self.schema.entry(config_value.key()) else {
+                return Err(Error::UnknownConfigKey {
+                    key: config_value.key().to_string(),
+                    line: config_value.line(),
+                });
+            };
+            if self.origins.get(entry.name()).is_some_and(|origin| origin.source() != Source::Config) {
+                continue;
+            }
+            let value = Value::parse(entry.arg_type(), config_value.value()).ok_or_else(|| redact(entry, Error::InvalidConfig {
+                key: config_value.key().to_string(),
+                line: config_value.line(),
+                arg_type: entry.arg_type().clone(),
+                value: config_value.value().to_string(),
+            }))?;
+            let name = entry.name().to_string();
+            let origin = Origin::Config {
+                path: config.path().map(|path| path.to_path_buf()),
+                line: config_value.line(),
+            };
+            self.insert(name, value, origin);
+        }
+
+        Ok(())
+    }
+
+    fn read_inputs(&mut self, input: &dyn Input) -> Result<(), Error> {
+        let mut stdin_read = false;
+
+        for entry in self.schema.entries().iter().filter(|entry| entry.reads_input()) {
+            // Only values typed on the command line name an input to read.
+            if self.origins.get(entry.name()).map(Origin::source) != Some(Source::Args) {
+                continue;
+            }
+            let Some(value) = self.strings.get_mut(entry.name()) else {
+                continue;
+            };
+            let error = |source: &str, message: String| Error::Input {
+                name: entry.name().to_string(),
+                source: source.to_string(),
+                message,
+            };
+            let contents = if value == "-" {
+                if stdin_read {
+                    return Err(error("stdin", "Standard input can only be read once".to_string()));
+                }
+                stdin_read = true;
+                input.read_stdin().map_err(|err| error("stdin", err.to_string()))?
+            } else if let Some(path) = value.strip_prefix('@').filter(|path| !path.is_empty()) {
+                input.read_file(Path::new(path)).map_err(|err| error(path, err.to_string()))?
+            } else {
+                continue;
+            };
+
+            *value = strip_newline(contents);
+        }
+
+        Ok(())
+    }
+
+    // Checks groups and relations between entries at once, so that every
+    // violation is reported together.
+    fn check_relations(&self) -> Result<(), Error> {
+        let mut errors = Vec::new();
+
+        for group in self.schema.groups() {
+            let given: Vec<&String> = group.members()
+                .iter()
+                .filter(|member| self.is_given(member))
+                .collect();
+            if group.allows(given.len()) {
+                continue;
+            }
+            errors.push(match given.is_empty() {
+                true => Error::MissingOneOf(group.members().iter().map(|member| self.spelling(member)).collect()),
+                false => Error::Conflict(given.into_iter().map(|member| self.spelling(member)).collect()),
+            });
+        }
+
+        let mut conflicts: Vec<(&str, &str)> = Vec::new();
+        for entry in self.schema.entries() {
+            let name = entry.name();
+            if self.is_given(name) {
+                for required in entry.requires().iter().filter(|required| !self.has_value(required)) {
+                    errors.push(Error::Requires {
+                        option: self.spelling(name),
+                        required: self.spelling(required),
+                    });
+                }
+                for other in entry.conflicts().iter().filter(|other| self.is_given(other)) {
+                    if conflicts.contains(&(other.as_str(), name)) {
+                        continue;
+                    }
+                    conflicts.push((name, other));
+                    errors.push(Error::Conflict(vec![self.spelling(name), self.spelling(other)]));
+                }
+            }
+            for (other, value) in entry.required_if() {
+                if !self.has_value(name) && self.value(other).as_ref() == Some(value) {
+                    errors.push(Error::RequiredIf {
+                        option: self.spelling(name),
+                        other: self.spelling(other),
+                        value: value.to_string(),
+                    });
+                }
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+
+    // Runs the validators of every entry on its value, whichever source
+    // gave it, once inputs have replaced their `-`/`@path` placeholders.
+    fn check_values(&self) -> Result<(), Error> {
+        for entry in self.schema.entries() {
+            let Some(value) = self.value(entry.name()).filter(|_| self.contains(entry.name())) else {
+                continue;
+            };
+            for validator in entry.validators() {
+                validator.check(&value).map_err(|message| redact(entry, Error::Validation {
+                    name: entry.name().to_string(),
+                    value: value.to_string(),
+                    message,
+                }))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_choices(&self) -> Result<(), Error> {
+        for entry in self.schema.entries() {
+            let Some(value) = self.strings.get(entry.name()) else {
+                continue;
+            };
+            if entry.choices().is_empty() || entry.choices().contains(value) {
+                continue;
+            }
+            return Err(redact(entry, Error::InvalidChoice {
+                name: entry.name().to_string(),
+                value: value.clone(),
+                choices: entry.choices().to_vec(),
+                suggestions: suggest(value, entry.choices().iter().map(String::as_str)),
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn check_paths(&self) -> Result<(), Error> {
+        for entry in self.schema.entries() {
+            let Some(Value::Path(path)) = self.value(entry.name()) else {
+                continue;
+            };
+            if let Some(constraint) = entry.path_constraints().iter().find(|constraint| !constraint.check(&path)) {
+                return Err(redact(entry, Error::Path {
+                    name: entry.name().to_string(),
+                    path,
+                    constraint: *constraint,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert(&mut self, name: String, value: Value, origin: Origin) {
+        self.origins.insert(name.clone(), origin);
+        match value {
+            Value::Bool(val) => {
+                self.bools.insert(name, val);
+            },
+            Value::Str(val) => {
+                self.strings.insert(name, val);
+            },
+            Value::Int(val) => {
+                self.ints.insert(name, val);
+            },
+            Value::OsStr(val) => {
+                self.os_strings.insert(name, val);
+            },
+            Value::Path(val) => {
+                self.paths.insert(name, val);
+            },
+        }
+    }
+
+    fn value(&self, key: &str) -> Option<Value> {
+        self.ints.get(key).map(|val| Value::Int(*val))
+            .or_else(|| self.strings.get(key).map(|val| Value::Str(val.clone())))
+            .or_else(|| self.bools.get(key).map(|val| Value::Bool(*val)))
+            .or_else(|| self.os_strings.get(key).map(|val| Value::OsStr(val.clone())))
+            .or_else(|| self.paths.get(key).map(|val| Value::Path(val.clone())))
+            .or_else(|| default_value(key, &self.schema).cloned())
+    }
+
+    // Every known value by entry name, with sensitive ones kept apart so
+    // that dumps can redact them.
+    fn dump(&self) -> BTreeMap<&str, (Value, bool)> {
+        self.schema.entries()
+            .iter()
+            .filter_map(|entry| Some((entry.name(), (self.value(entry.name())?, entry.is_sensitive()))))
+            .collect()
+    }
+
+    // Inserts a value given on the command line, applying the duplicates
+    // policy of its entry when it was given before.
+    fn insert_arg(&mut self, name: String, value: Value, origin: Origin, spelling: Option<String>) -> Result<(), Error> {
+        if self.origins.get(&name).map(Origin::source) == Some(Source::Args) {
+            let entry = self.schema.entry(&name).expect("Parsed arguments are defined");
+            match self.schema.duplicates(entry) {
+                Duplicates::LastWins => (),
+                Duplicates::FirstWins => return Ok(()),
+                Duplicates::Error => {
+                    let spelling = spelling.unwrap_or_else(|| self.spelling(&name));
+                    return Err(Error::Duplicate(spelling));
+                },
+            }
+        }
+
+        if let Some(spelling) = spelling {
+            self.spellings.insert(name.clone(), spelling);
+        }
+        self.insert(name, value, origin);
+        Ok(())
+    }
+
+    // The option as the user typed it, for error messages.
+    fn spelling(&self, name: &str) -> String {
+        match (self.origins.get(name), self.spellings.get(name)) {
+            (Some(Origin::Env(var)), _) => format!("{var} (environment)"),
+            (Some(Origin::Config { path: Some(path), line }), _) => format!("{name} ({}:{line})", path.display()),
+            (Some(Origin::Config { path: None, line }), _) => format!("{name} (config line {line})"),
+            (_, Some(spelling)) => spelling.clone(),
+            (_, None) => self.schema.spelling(name),
+        }
+    }
+
+    // Whether a value was given by any source other than the defaults; a
+    // flag only counts when it is set.
+    fn is_given(&self, name: &str) -> bool {
+        match self.bools.get(name) {
+            Some(val) => *val,
+            None => self.contains(name),
+        }
+    }
+
+    // Whether the entry ends up with a value, possibly its default.
+    fn has_value(&self, name: &str) -> bool {
+        match self.bools.get(name) {
+            Some(val) => *val,
+            None => self.value(name).is_some_and(|value| value != Value::Bool(false)),
+        }
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.ints.contains_key(key)
+            || self.strings.contains_key(key)
+            || self.bools.contains_key(key)
+            || self.os_strings.contains_key(key)
+            || self.paths.contains_key(key)
+    }
+}
+
+impl fmt::Debug for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, (value, sensitive)) in self.dump() {
+            match sensitive {
+                true => map.entry(&name, &crate::sensitive::Sensitive::new(value)),
+                false => map.entry(&name, &value),
+            };
+        }
+        map.finish()
+    }
+}
+
+#[cfg(any(feature = "toml", feature = "json"))]
+impl serde::Serialize for Collection {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let dump = self.dump();
+        let mut map = serializer.serialize_map(Some(dump.len()))?;
+        for (name, (value, sensitive)) in dump {
+            match sensitive {
+                true => map.serialize_entry(name, &crate::sensitive::Sensitive::new(value))?,
+                false => map.serialize_entry(name, &value)?,
+            }
+        }
+        map.end()
+    }
+}
+
+fn tokens_from_args(args: Vec<&str>, schema: Schema, env: &dyn Env) -> Tokens {
+    let parser = TokenParser::new()
+        .args(args)
+        .schema(schema)
+        .posixly_correct(posixly_correct(env));
+
+    parser.collect()
+}
+
+fn strip_newline(mut contents: String) -> String {
+    if contents.ends_with('\n') {
+        contents.pop();
+        if contents.ends_with('\r') {
+            contents.pop();
+        }
+    }
+    contents
+}
+
+fn redact(entry: &Entry, err: Error) -> Error {
+    match entry.is_sensitive() {
+        true => err.redacted(),
+        false => err,
+    }
+}
+
+pub(crate) fn token_value(token: &Token) -> Option<Value> {
+    match token {
+        StrValue(val) => Some(Value::Str(val.clone())),
+        IntValue(val) => Some(Value::Int(*val)),
+        OsStrValue(val) => Some(Value::OsStr(val.clone())),
+        PathValue(val) => Some(Value::Path(val.clone())),
+        Sensitive(token) => token_value(token.expose()),
+        AppName | Argument(..) | Subcommand(_) | Unknown(_) | Positional(_) => None,
+    }
+}
+
+fn args_origin(span: Option<&Span>) -> Origin {
+    let span = span.expect("Span expected");
+
+    match &span.file {
+        Some((path, line)) => Origin::ResponseFile {
+            index: span.index,
+            path: path.clone(),
+            line: *line,
+        },
+        None => Origin::Args {
+            index: span.index,
+            range: span.range.clone(),
+        },
+    }
 }
 
 fn argument_type_matches(key: &str, schema: &Schema, arg_type: ArgumentType) -> bool {
-    if let Some(valid_key) = schema.get(key.chars().next().expect("Valid string expected")) {
-        if valid_key == arg_type {
+    if let Some(entry) = schema.entry(key) {
+        if *entry.arg_type() == arg_type {
             return true;
         }
     }
     false
 }
 
+fn default_value<'a>(key: &str, schema: &'a Schema) -> Option<&'a Value> {
+    schema.entry(key)?.default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::group::GroupKind;
+    use crate::schema::order::ArgumentOrder;
+    use crate::schema::path::PathConstraint;
+    use crate::schema::entry::Entry;
 
     #[test]
     fn should_get_values_from_collection() {
@@ -314,4 +846,735 @@ mod tests {
         // then
         collection.get_bool("s");
     }
+
+    #[test]
+    fn should_return_defaults_when_not_set() {
+        // given
+        let schema = Schema::from_entries(vec![
+            Entry::new("count", ArgumentType::Int).with_default(Value::Int(1)),
+            Entry::new("output", ArgumentType::Str).with_default(Value::Str("out.txt".to_string())),
+            Entry::new("color", ArgumentType::Bool).with_default(Value::Bool(true)),
+        ]);
+
+        // when
+        let collection = Collection::from_args(vec!["app_name", "--count", "3"], schema);
+
+        // then
+        assert_eq!(collection.get_int("count"), Some(3));
+        assert_eq!(collection.get_str("output"), Some("out.txt"));
+        assert!(collection.get_bool("color"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing required argument: count")]
+    fn should_panic_when_required_argument_missing() {
+        // given
+        let schema = Schema::from_entries(vec![
+            Entry::new("count", ArgumentType::Int).with_required(true),
+        ]);
+
+        // when
+        Collection::from_args(vec!["app_name"], schema);
+    }
+
+    #[test]
+    fn should_collect_arguments_of_fluent_schema() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .option::<i64>('n', "count").default(1)
+            .option::<String>('o', "output")
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args(vec!["app_name", "-v", "--output", "out.txt"], schema);
+
+        // then
+        assert!(collection.get_bool("verbose"));
+        assert_eq!(collection.get_int("count"), Some(1));
+        assert_eq!(collection.get_str("output"), Some("out.txt"));
+    }
+
+    #[test]
+    fn should_fall_back_to_environment_variables() {
+        // given
+        let schema = Schema::builder()
+            .env_prefix("MYTOOL")
+            .flag('v', "verbose")
+            .option::<i64>('n', "count").env("COUNT")
+            .option::<String>('o', "output")
+            .build()
+            .unwrap();
+        let env = vec![
+            ("MYTOOL_VERBOSE", "true"),
+            ("COUNT", "3"),
+            ("MYTOOL_OUTPUT", "env.txt"),
+        ];
+
+        // when
+        let collection = Collection::from_args_with_env(vec!["app_name", "-o", "argv.txt"], schema, &env);
+
+        // then
+        assert!(collection.get_bool("verbose"));
+        assert_eq!(collection.get_int("count"), Some(3));
+        assert_eq!(collection.get_str("output"), Some("argv.txt"));
+    }
+
+    #[test]
+    fn should_satisfy_required_argument_from_environment() {
+        // given
+        let schema = Schema::builder()
+            .option::<i64>('n', "count").env("COUNT").required()
+            .build()
+            .unwrap();
+        let env = vec![("COUNT", "7")];
+
+        // when
+        let collection = Collection::from_args_with_env(vec!["app_name"], schema, &env);
+
+        // then
+        assert_eq!(collection.get_int("count"), Some(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid int in environment variable COUNT: seven")]
+    fn should_panic_on_invalid_environment_value() {
+        // given
+        let schema = Schema::builder()
+            .option::<i64>('n', "count").env("COUNT")
+            .build()
+            .unwrap();
+        let env = vec![("COUNT", "seven")];
+
+        // when
+        Collection::from_args_with_env(vec!["app_name"], schema, &env);
+    }
+
+    #[test]
+    fn should_record_origin_of_values() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .option::<i64>('n', "count").default(1)
+            .option::<String>('o', "output").env("OUTPUT")
+            .option::<String>('i', "input")
+            .option::<String>('l', "label")
+            .build()
+            .unwrap();
+        let env = vec![("OUTPUT", "env.txt")];
+
+        // when
+        let collection = Collection::from_args_with_env(vec!["app_name", "-v", "--input=in.txt", "-l", "x"], schema, &env);
+
+        // then
+        assert_eq!(collection.origin("verbose"), Some(Origin::Args { index: 1, range: 0..2 }));
+        assert_eq!(collection.origin("input"), Some(Origin::Args { index: 2, range: 8..14 }));
+        assert_eq!(collection.origin("label"), Some(Origin::Args { index: 4, range: 0..1 }));
+        assert_eq!(collection.origin("output"), Some(Origin::Env("OUTPUT".to_string())));
+        assert_eq!(collection.origin("count"), Some(Origin::Default));
+    }
+
+    #[test]
+    fn should_record_config_file_origin() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("args.ini");
+        std::fs::write(&path, "# defaults
+count = 5
+").unwrap();
+        let schema = Schema::builder()
+            .option::<i64>('n', "count")
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::builder()
+            .schema(schema)
+            .config(Config::from_file(&path).unwrap())
+            .env(&Vec::new())
+            .args(vec!["app_name"])
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_int("count"), Some(5));
+        assert_eq!(collection.origin("count"), Some(Origin::Config { path: Some(path), line: 2 }));
+    }
+
+    #[test]
+    fn should_collect_from_command_line() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .option::<i64>('n', "count")
+            .option::<String>('m', "message")
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_command_line(r#"deploy -v -n 3 -m "it's \"done\"""#, schema).unwrap();
+
+        // then
+        assert!(collection.get_bool("verbose"));
+        assert_eq!(collection.get_int("count"), Some(3));
+        assert_eq!(collection.get_str("message"), Some(r#"it's "done""#));
+    }
+
+    #[test]
+    fn should_not_read_process_environment_for_command_line() {
+        // given
+        let schema = Schema::builder()
+            .option::<String>('p', "path").env("PATH")
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_command_line("deploy", schema).unwrap();
+
+        // then
+        assert_eq!(collection.get_str("path"), None);
+    }
+
+    #[test]
+    fn should_not_read_inputs_for_command_line() {
+        // given
+        let schema = Schema::builder()
+            .option::<String>('t', "token").read_input()
+            .build()
+            .unwrap();
+
+        // when
+        let stdin = Collection::from_command_line("deploy -t -", schema.clone());
+        let file = Collection::from_command_line("deploy -t @/etc/passwd", schema);
+
+        // then
+        assert_eq!(
+            stdin.err().map(|err| err.to_string()),
+            Some("Cannot read value for token from stdin: Reading input is disabled".to_string()),
+        );
+        assert_eq!(
+            file.err().map(|err| err.to_string()),
+            Some("Cannot read value for token from /etc/passwd: Reading input is disabled".to_string()),
+        );
+    }
+
+    #[test]
+    fn should_report_unterminated_quote_in_command_line() {
+        // given
+        let schema = Schema::builder()
+            .option::<String>('m', "message")
+            .build()
+            .unwrap();
+
+        // when
+        let result = Collection::from_command_line("deploy -m 'oops", schema);
+
+        // then
+        assert_eq!(result.err().map(|err| err.to_string()), Some("Unterminated single quote at line 1".to_string()));
+    }
+
+    #[test]
+    fn should_collect_from_os_args() {
+        // given
+        let schema = Schema::builder()
+            .option::<OsString>('f', "file")
+            .option::<String>('s', "string")
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args_os(["app_name", "-f", "a.txt", "-s", "foo"], schema).unwrap();
+
+        // then
+        assert_eq!(collection.get_os_str("file"), Some(OsStr::new("a.txt")));
+        assert_eq!(collection.get_str("string"), Some("foo"));
+    }
+
+    #[test]
+    fn should_return_error_when_value_missing() {
+        // given
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+
+        // when
+        let result = Collection::from_args_os(["app_name", "-s"], schema);
+
+        // then
+        assert_eq!(result.err(), Some(Error::MissingValue("s".to_string())));
+    }
+
+    #[test]
+    fn should_return_error_for_unknown_argument() {
+        // given
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+
+        // when
+        let result = Collection::from_args_os(["app_name", "-x"], schema);
+
+        // then
+        assert_eq!(result.err().map(|err| err.to_string()), Some("Expects valid argument: -x".to_string()));
+    }
+
+    #[test]
+    fn should_check_path_constraints() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        std::fs::write(&input, "").unwrap();
+        let output = dir.path().join("output.txt");
+        let schema = || Schema::builder()
+            .option::<PathBuf>('i', "input").constraint(PathConstraint::IsFile)
+            .option::<PathBuf>('o', "output").constraint(PathConstraint::NotExists).constraint(PathConstraint::ParentExists)
+            .option::<PathBuf>('d', "dir").constraint(PathConstraint::IsDir)
+            .build()
+            .unwrap();
+
+        // when
+        let valid = Collection::from_args_os(
+            [OsStr::new("app_name"), OsStr::new("-i"), input.as_os_str(), OsStr::new("-o"), output.as_os_str()],
+            schema(),
+        );
+        let invalid = Collection::from_args_os(
+            [OsStr::new("app_name"), OsStr::new("-d"), input.as_os_str()],
+            schema(),
+        );
+
+        // then
+        let valid = valid.unwrap();
+        assert_eq!(valid.get_path("input"), Some(input.as_path()));
+        assert_eq!(valid.get_path("output"), Some(output.as_path()));
+        assert_eq!(valid.get_path("dir"), None);
+        assert_eq!(invalid.err(), Some(Error::Path {
+            name: "dir".to_string(),
+            path: input.clone(),
+            constraint: PathConstraint::IsDir,
+        }));
+    }
+
+    #[test]
+    fn should_check_path_constraints_of_defaults() {
+        // given
+        let schema = Schema::builder()
+            .option::<PathBuf>('c', "config").default(PathBuf::from("/nonexistent/x")).constraint(PathConstraint::Exists)
+            .build()
+            .unwrap();
+
+        // when
+        let result = Collection::from_args_os(["app_name"], schema);
+
+        // then
+        assert_eq!(result.err(), Some(Error::Path {
+            name: "config".to_string(),
+            path: PathBuf::from("/nonexistent/x"),
+            constraint: PathConstraint::Exists,
+        }));
+    }
+
+    #[test]
+    fn should_report_missing_parent_directory() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("missing/output.txt");
+        let schema = Schema::builder()
+            .option::<PathBuf>('o', "output").constraint(PathConstraint::ParentExists)
+            .build()
+            .unwrap();
+
+        // when
+        let result = Collection::from_args_os([OsStr::new("app_name"), OsStr::new("--output"), output.as_os_str()], schema);
+
+        // then
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some(format!("Path for output has no existing parent directory: {}", output.display())),
+        );
+    }
+
+    fn secret_schema() -> Schema {
+        Schema::builder()
+            .option::<String>('t', "token").sensitive()
+            .option::<i64>('p', "port").sensitive()
+            .option::<String>('u', "user")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_redact_sensitive_values_in_debug_output() {
+        // given
+        let tokens = TokenParser::new()
+            .args(vec!["app_name", "-t", "hunter2", "--port=8080", "-u", "alice"])
+            .schema(secret_schema())
+            .collect();
+        let token_dump = format!("{tokens:?}");
+
+        // when
+        let collection = Collection::from(tokens);
+
+        // then
+        let dump = format!("{collection:?}");
+        assert_eq!(dump, r#"{"port": ***, "token": ***, "user": Str("alice")}"#);
+        assert!(!token_dump.contains("hunter2") && !token_dump.contains("8080"), "{token_dump}");
+        assert_eq!(collection.get_str("token"), Some("hunter2"));
+        assert_eq!(collection.get_int("port"), Some(8080));
+    }
+
+    #[test]
+    fn should_redact_sensitive_values_in_errors() {
+        // given
+        let env = vec![("PORT", "eighty")];
+        let schema = Schema::builder()
+            .option::<i64>('p', "port").sensitive().env("PORT")
+            .build()
+            .unwrap();
+
+        // when
+        let parse_error = Collection::from_args_os(["app_name", "--port=eighty"], secret_schema()).err();
+        let env_error = Collection::builder()
+            .schema(schema)
+            .env(&env)
+            .args(vec!["app_name"])
+            .build()
+            .err();
+
+        // then
+        assert_eq!(parse_error.map(|err| err.to_string()), Some("Invalid int for port: ***".to_string()));
+        assert_eq!(env_error.map(|err| err.to_string()), Some("Invalid int in environment variable PORT: ***".to_string()));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn should_redact_sensitive_values_when_serialized() {
+        // given
+        let collection = Collection::from_args(vec!["app_name", "-t", "hunter2", "-u", "alice"], secret_schema());
+
+        // when
+        let json = serde_json::to_string(&collection).unwrap();
+
+        // then
+        assert_eq!(json, r#"{"token":"***","user":"alice"}"#);
+    }
+
+    #[test]
+    fn should_collect_values_of_subcommand() {
+        // given
+        let remote = Schema::builder()
+            .option::<String>('n', "name")
+            .build()
+            .unwrap();
+        let schema = Schema::builder()
+            .flag('q', "quiet")
+            .subcommand("remote", remote)
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args(vec!["app_name", "-q", "remote", "-n", "origin"], schema);
+
+        // then
+        assert!(collection.get_bool("quiet"));
+        let (name, remote) = collection.subcommand().unwrap();
+        assert_eq!(name, "remote");
+        assert_eq!(remote.get_str("name"), Some("origin"));
+        assert!(remote.subcommand().is_none());
+    }
+
+    #[test]
+    fn should_reject_values_outside_choices() {
+        // given
+        let schema = Schema::builder()
+            .option::<String>('c', "color").choices(&["always", "auto", "never"])
+            .build()
+            .unwrap();
+
+        // when
+        let valid = Collection::from_args_os(["app_name", "--color", "auto"], schema.clone());
+        let invalid = Collection::from_args_os(["app_name", "--color", "allways"], schema);
+
+        // then
+        assert_eq!(valid.unwrap().get_str("color"), Some("auto"));
+        assert_eq!(
+            invalid.err().map(|err| err.to_string()),
+            Some("Invalid value for color: allways, expected one of: always, auto, never (did you mean always?)".to_string()),
+        );
+    }
+
+    fn output_schema(kind: GroupKind) -> Schema {
+        Schema::builder()
+            .flag('j', "json").alias("as-json")
+            .flag('t', "table")
+            .option::<String>('f', "file")
+            .group(kind, &["json", "table"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_report_conflicting_options_as_typed() {
+        // when
+        let result = Collection::from_args_os(["app_name", "--as-json", "-t"], output_schema(GroupKind::AtMostOne));
+
+        // then
+        assert_eq!(result.err(), Some(Error::Conflict(vec!["--as-json".to_string(), "-t".to_string()])));
+    }
+
+    #[test]
+    fn should_require_exactly_one_option_of_group() {
+        // when
+        let none = Collection::from_args_os(["app_name", "-f", "out"], output_schema(GroupKind::ExactlyOne));
+        let one = Collection::from_args_os(["app_name", "-j"], output_schema(GroupKind::ExactlyOne));
+        let both = Collection::from_args_os(["app_name", "-j", "--table"], output_schema(GroupKind::ExactlyOne));
+
+        // then
+        assert_eq!(none.err().map(|err| err.to_string()), Some("Expects one of: --json, --table".to_string()));
+        assert!(one.is_ok());
+        assert_eq!(both.err().map(|err| err.to_string()), Some("Options cannot be used together: -j, --table".to_string()));
+    }
+
+    #[test]
+    fn should_count_options_given_through_environment() {
+        // given
+        let env = vec![("TABLE", "1")];
+        let schema = Schema::builder()
+            .flag('j', "json")
+            .flag('t', "table").env("TABLE")
+            .group(GroupKind::AtLeastOne, &["json", "table"])
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args_with_env(vec!["app_name"], schema, &env);
+
+        // then
+        assert!(collection.get_bool("table"));
+    }
+
+    #[test]
+    fn should_name_source_of_options_not_given_as_arguments() {
+        // given
+        let env = vec![("TABLE", "1")];
+        let schema = Schema::builder()
+            .flag('j', "json")
+            .flag('t', "table").env("TABLE")
+            .group(GroupKind::AtMostOne, &["json", "table"])
+            .build()
+            .unwrap();
+
+        // when
+        let result = Collection::builder()
+            .schema(schema)
+            .env(&env)
+            .args(vec!["app_name", "--json"])
+            .build();
+
+        // then
+        assert_eq!(result.err(), Some(Error::Conflict(vec!["--json".to_string(), "TABLE (environment)".to_string()])));
+    }
+
+    fn tls_schema() -> Schema {
+        Schema::builder()
+            .option::<String>('m', "mode").default("plain")
+            .option::<String>('k', "key").requires("cert").conflicts_with("password")
+            .option::<String>('c', "cert").required_if("mode", "tls")
+            .option::<String>('p', "password").conflicts_with("key")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_accept_satisfied_relations() {
+        // when
+        let result = Collection::from_args_os(["app_name", "-m", "tls", "--key", "k.pem", "--cert", "c.pem"], tls_schema());
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_report_all_violated_relations_together() {
+        // when
+        let result = Collection::from_args_os(["app_name", "--mode", "tls", "-k", "k.pem", "--password", "secret"], tls_schema());
+
+        // then
+        assert_eq!(result.err(), Some(Error::Multiple(vec![
+            Error::Requires { option: "-k".to_string(), required: "--cert".to_string() },
+            Error::Conflict(vec!["-k".to_string(), "--password".to_string()]),
+            Error::RequiredIf { option: "--cert".to_string(), other: "--mode".to_string(), value: "tls".to_string() },
+        ])));
+    }
+
+    #[test]
+    fn should_report_single_violation_on_its_own() {
+        // when
+        let result = Collection::from_args_os(["app_name", "-m", "tls"], tls_schema());
+
+        // then
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Option --cert is required when -m is tls".to_string()),
+        );
+    }
+
+    fn repeated_schema(duplicates: Duplicates) -> Schema {
+        Schema::builder()
+            .duplicates(duplicates)
+            .option::<String>('s', "signal")
+            .option::<i64>('n', "count").duplicates(Duplicates::LastWins)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_apply_duplicates_policy_of_schema() {
+        // given
+        let args = ["app_name", "-s", "TERM", "--signal", "KILL"];
+
+        // when
+        let last = Collection::from_args_os(args, repeated_schema(Duplicates::LastWins)).unwrap();
+        let first = Collection::from_args_os(args, repeated_schema(Duplicates::FirstWins)).unwrap();
+        let error = Collection::from_args_os(args, repeated_schema(Duplicates::Error));
+
+        // then
+        assert_eq!(last.get_str("signal"), Some("KILL"));
+        assert_eq!(first.get_str("signal"), Some("TERM"));
+        assert_eq!(first.origin("signal"), Some(Origin::Args { index: 2, range: 0..4 }));
+        assert_eq!(error.err().map(|err| err.to_string()), Some("Option --signal given more than once".to_string()));
+    }
+
+    #[test]
+    fn should_prefer_duplicates_policy_of_entry() {
+        // when
+        let collection = Collection::from_args_os(["app_name", "-n", "1", "-n", "2"], repeated_schema(Duplicates::Error));
+
+        // then
+        assert_eq!(collection.unwrap().get_int("count"), Some(2));
+    }
+
+    #[test]
+    fn should_not_count_other_sources_as_duplicates() {
+        // given
+        let env = vec![("SIGNAL", "TERM")];
+        let schema = Schema::builder()
+            .duplicates(Duplicates::Error)
+            .option::<String>('s', "signal").env("SIGNAL")
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args_with_env(vec!["app_name", "-s", "KILL"], schema, &env);
+
+        // then
+        assert_eq!(collection.get_str("signal"), Some("KILL"));
+    }
+
+    #[test]
+    fn should_collect_unknown_options_for_passthrough() {
+        // given
+        let schema = Schema::builder()
+            .option::<String>('s', "signal")
+            .collect_unknown(true)
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args_os(["app_name", "-k", "5", "-s", "KILL", "--foreground"], schema).unwrap();
+
+        // then
+        assert_eq!(collection.get_str("signal"), Some("KILL"));
+        assert_eq!(collection.unknown(), ["-k", "5", "--foreground"].map(OsString::from));
+    }
+
+    #[test]
+    fn should_honour_posixly_correct_from_environment() {
+        // given
+        let env = vec![("POSIXLY_CORRECT", "1")];
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .positionals(true)
+            .build()
+            .unwrap();
+
+        // when
+        let permuted = Collection::from_args_with_env(vec!["app_name", "cmd", "-v"], schema.clone(), &Vec::new());
+        let posix = Collection::from_args_with_env(vec!["app_name", "cmd", "-v"], schema, &env);
+
+        // then
+        assert!(permuted.get_bool("verbose"));
+        assert_eq!(permuted.positionals(), ["cmd"].map(OsString::from));
+        assert!(!posix.get_bool("verbose"));
+        assert_eq!(posix.positionals(), ["cmd", "-v"].map(OsString::from));
+    }
+
+    fn validated_schema() -> Schema {
+        Schema::builder()
+            .option::<i64>('p', "port").min(1).max(65535)
+            .option::<String>('u', "user").non_empty().max_length(8)
+            .option::<String>('t', "token").sensitive().min_length(12)
+            .option::<std::path::PathBuf>('o', "output")
+            .validate(|path| match path.extension() {
+                Some(extension) if extension == "json" => Ok(()),
+                _ => Err("must be a .json file".to_string()),
+            })
+            .build()
+            .unwrap()
+    }
+
+    fn validation_error(args: Vec<&str>) -> Option<String> {
+        Collection::from_args_os(args, validated_schema())
+            .err()
+            .map(|err| err.to_string())
+    }
+
+    #[test]
+    fn should_accept_valid_values() {
+        // when
+        let error = validation_error(vec!["app_name", "-p", "8080", "--user=alice", "-t", "0123456789abc", "-o", "out.json"]);
+
+        // then
+        assert_eq!(error, None);
+    }
+
+    #[test]
+    fn should_reject_values_failing_validators() {
+        // then
+        assert_eq!(validation_error(vec!["app_name", "-p", "0"]), Some("Invalid value for port: 0, must be at least 1".to_string()));
+        assert_eq!(validation_error(vec!["app_name", "--port=70000"]), Some("Invalid value for port: 70000, must be at most 65535".to_string()));
+        assert_eq!(validation_error(vec!["app_name", "-u", ""]), Some("Invalid value for user: , must not be empty".to_string()));
+        assert_eq!(validation_error(vec!["app_name", "-o", "out.txt"]), Some("Invalid value for output: out.txt, must be a .json file".to_string()));
+    }
+
+    #[test]
+    fn should_redact_rejected_sensitive_values() {
+        // then
+        assert_eq!(
+            validation_error(vec!["app_name", "--token=short"]),
+            Some("Invalid value for token: ***, must be at least 12 characters long".to_string()),
+        );
+        assert_eq!(
+            validation_error(vec!["app_name", "-t", "short"]),
+            Some("Invalid value for token: ***, must be at least 12 characters long".to_string()),
+        );
+    }
+
+    #[test]
+    fn should_leave_positionals_to_schema_when_collecting_unknown_options() {
+        // given
+        let schema = Schema::builder()
+            .collect_unknown(true)
+            .positionals(true)
+            .order(ArgumentOrder::Posix)
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::from_args_with_env(vec!["timeout", "--foreground", "cmd", "-x"], schema, &Vec::new());
+
+        // then
+        assert_eq!(collection.unknown(), ["--foreground"].map(OsString::from));
+        assert_eq!(collection.positionals(), ["cmd", "-x"].map(OsString::from));
+    }
 }
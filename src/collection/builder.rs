@@ -0,0 +1,455 @@
+use crate::collection::config::Config;
+use crate::collection::Collection;
+use crate::env::{posixly_correct, Env, ProcessEnv};
+use crate::error::Error;
+use crate::input::{Input, StdInput};
+use crate::schema::Schema;
+use crate::token::parser::TokenParser;
+use crate::token::split::split;
+
+pub struct CollectionBuilder<'a> {
+    schema: Option<Schema>,
+    config: Option<Config>,
+    env: &'a dyn Env,
+    input: &'a dyn Input,
+    args: Vec<String>,
+    command_line: Option<String>,
+    response_files: bool,
+}
+
+impl Default for CollectionBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            schema: None,
+            config: None,
+            env: &ProcessEnv,
+            input: &StdInput,
+            args: Vec::new(),
+            command_line: None,
+            response_files: false,
+        }
+    }
+}
+
+impl<'a> CollectionBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn schema(self, schema: Schema) -> Self {
+        Self {
+            schema: Some(schema),
+            ..self
+        }
+    }
+
+    pub fn config(self, config: Config) -> Self {
+        Self {
+            config: Some(config),
+            ..self
+        }
+    }
+
+    pub fn env(self, env: &'a dyn Env) -> Self {
+        Self {
+            env,
+            ..self
+        }
+    }
+
+    pub fn input(self, input: &'a dyn Input) -> Self {
+        Self {
+            input,
+            ..self
+        }
+    }
+
+    pub fn args(self, args: Vec<&str>) -> Self {
+        Self {
+            args: args.into_iter().map(String::from).collect(),
+            ..self
+        }
+    }
+
+    /// Takes the arguments from a single string, split with POSIX shell
+    /// rules, instead of from `args`.
+    pub fn command_line(self, command_line: &str) -> Self {
+        Self {
+            command_line: Some(command_line.to_string()),
+            ..self
+        }
+    }
+
+    pub fn response_files(self, response_files: bool) -> Self {
+        Self {
+            response_files,
+            ..self
+        }
+    }
+
+    pub fn build(self) -> Result<Collection, Error> {
+        let args = match &self.command_line {
+            Some(command_line) => split(command_line)?,
+            None => self.args,
+        };
+        let tokens = TokenParser::new()
+            .args(args.iter().map(String::as_str).collect())
+            .schema(self.schema.expect("Schema expected"))
+            .response_files(self.response_files)
+            .posixly_correct(posixly_correct(self.env))
+            .try_collect()?;
+
+        Collection::from_sources(tokens, self.env, self.config.as_ref(), self.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::source::{Origin, Source};
+
+    fn schema() -> Schema {
+        Schema::builder()
+            .env_prefix("MYTOOL")
+            .flag('v', "verbose")
+            .option::<i64>('n', "count").default(1)
+            .option::<String>('o', "output").default("default.txt")
+            .option::<String>('i', "input")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_apply_sources_in_order_of_precedence() {
+        // given
+        let config = Config::from_ini_str("count = 2\noutput = config.txt\ninput = config.in\n").unwrap();
+        let env = vec![("MYTOOL_OUTPUT", "env.txt"), ("MYTOOL_INPUT", "env.in")];
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(schema())
+            .config(config)
+            .env(&env)
+            .args(vec!["app_name", "-i", "argv.in"])
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("input"), Some("argv.in"));
+        assert_eq!(collection.source("input"), Some(Source::Args));
+        assert_eq!(collection.get_str("output"), Some("env.txt"));
+        assert_eq!(collection.source("output"), Some(Source::Env));
+        assert_eq!(collection.get_int("count"), Some(2));
+        assert_eq!(collection.source("count"), Some(Source::Config));
+        assert!(!collection.get_bool("verbose"));
+        assert_eq!(collection.source("verbose"), None);
+    }
+
+    #[test]
+    fn should_record_schema_default_as_source() {
+        // given
+        let env: Vec<(&str, &str)> = Vec::new();
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(schema())
+            .env(&env)
+            .args(vec!["app_name"])
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_int("count"), Some(1));
+        assert_eq!(collection.source("count"), Some(Source::Default));
+    }
+
+    #[test]
+    fn should_reject_unknown_config_keys() {
+        // given
+        let config = Config::from_ini_str("count = 2\ncolour = red\n").unwrap();
+
+        // when
+        let result = CollectionBuilder::new()
+            .schema(schema())
+            .config(config)
+            .env(&Vec::new())
+            .args(vec!["app_name"])
+            .build();
+
+        // then
+        assert_eq!(result.err(), Some(Error::UnknownConfigKey { key: "colour".to_string(), line: 2 }));
+    }
+
+    #[test]
+    fn should_reject_invalid_config_values() {
+        // given
+        let config = Config::from_ini_str("count = two\n").unwrap();
+
+        // when
+        let result = CollectionBuilder::new()
+            .schema(schema())
+            .config(config)
+            .env(&Vec::new())
+            .args(vec!["app_name"])
+            .build();
+
+        // then
+        assert_eq!(result.err().map(|err| err.to_string()), Some("Invalid int for count in config at line 1: two".to_string()));
+    }
+
+    #[test]
+    fn should_expand_response_files() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        std::fs::write(&file, "--input 'my file.in' -v").unwrap();
+        let at_file = format!("@{}", file.display());
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(schema())
+            .env(&Vec::new())
+            .args(vec!["app_name", &at_file])
+            .response_files(true)
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("input"), Some("my file.in"));
+        assert!(collection.get_bool("verbose"));
+    }
+
+    #[test]
+    fn should_record_origins_relative_to_command_line() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        std::fs::write(&file, "-v -n 2\n-o out.txt").unwrap();
+        let at_file = format!("@{}", file.display());
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(schema())
+            .env(&Vec::new())
+            .args(vec!["app_name", &at_file, "-i", "argv.in"])
+            .response_files(true)
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.origin("input"), Some(Origin::Args { index: 3, range: 0..7 }));
+        assert_eq!(collection.origin("output"), Some(Origin::ResponseFile { index: 1, path: file, line: 2 }));
+        assert_eq!(collection.source("output"), Some(Source::Args));
+    }
+
+    #[test]
+    fn should_split_command_line() {
+        // given
+        let env = vec![("MYTOOL_OUTPUT", "env.txt")];
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(schema())
+            .env(&env)
+            .command_line("app_name -i 'my file.in'")
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("input"), Some("my file.in"));
+        assert_eq!(collection.get_str("output"), Some("env.txt"));
+    }
+
+    #[test]
+    fn should_return_response_file_errors() {
+        // when
+        let result = CollectionBuilder::new()
+            .schema(schema())
+            .env(&Vec::new())
+            .args(vec!["app_name", "@/nonexistent/args.txt"])
+            .response_files(true)
+            .build();
+
+        // then
+        assert!(matches!(result.err(), Some(Error::ResponseFile { .. })));
+    }
+
+    struct FakeInput {
+        stdin: &'static str,
+    }
+
+    impl Input for FakeInput {
+        fn read_stdin(&self) -> std::io::Result<String> {
+            Ok(self.stdin.to_string())
+        }
+
+        fn read_file(&self, path: &std::path::Path) -> std::io::Result<String> {
+            match path.to_str() {
+                Some("payload.json") => Ok("{\"a\": 1}\n".to_string()),
+                _ => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found")),
+            }
+        }
+    }
+
+    fn input_schema() -> Schema {
+        Schema::builder()
+            .option::<String>('k', "key").read_input()
+            .option::<String>('p', "payload").read_input()
+            .option::<String>('l', "label")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_read_values_from_stdin_and_files() {
+        // given
+        let input = FakeInput { stdin: "s3cret\n" };
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(input_schema())
+            .env(&Vec::new())
+            .input(&input)
+            .args(vec!["app_name", "-k", "-", "-p", "@payload.json", "-l", "@literal"])
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("key"), Some("s3cret"));
+        assert_eq!(collection.get_str("payload"), Some("{\"a\": 1}"));
+        assert_eq!(collection.get_str("label"), Some("@literal"));
+    }
+
+    #[test]
+    fn should_leave_input_values_to_their_entry_when_expanding_response_files() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        std::fs::write(&file, "-l label").unwrap();
+        let at_file = format!("@{}", file.display());
+        let input = FakeInput { stdin: "" };
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(input_schema())
+            .env(&Vec::new())
+            .input(&input)
+            .args(vec!["app_name", &at_file, "-p", "@payload.json"])
+            .response_files(true)
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("label"), Some("label"));
+        assert_eq!(collection.get_str("payload"), Some("{\"a\": 1}"));
+    }
+
+    #[test]
+    fn should_keep_input_placeholders_from_other_sources_literal() {
+        // given
+        let input = FakeInput { stdin: "s3cret" };
+        let env = vec![("KEY", "@payload.json")];
+        let config = Config::from_ini_str("payload = -\n").unwrap();
+        let schema = Schema::builder()
+            .option::<String>('k', "key").read_input().env("KEY")
+            .option::<String>('p', "payload").read_input()
+            .build()
+            .unwrap();
+
+        // when
+        let collection = CollectionBuilder::new()
+            .schema(schema)
+            .config(config)
+            .env(&env)
+            .input(&input)
+            .args(vec!["app_name"])
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("key"), Some("@payload.json"));
+        assert_eq!(collection.get_str("payload"), Some("-"));
+    }
+
+    #[test]
+    fn should_read_stdin_only_once() {
+        // given
+        let input = FakeInput { stdin: "s3cret" };
+
+        // when
+        let result = CollectionBuilder::new()
+            .schema(input_schema())
+            .env(&Vec::new())
+            .input(&input)
+            .args(vec!["app_name", "-k", "-", "-p", "-"])
+            .build();
+
+        // then
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Cannot read value for payload from stdin: Standard input can only be read once".to_string()),
+        );
+    }
+
+    #[test]
+    fn should_report_unreadable_files() {
+        // given
+        let input = FakeInput { stdin: "" };
+
+        // when
+        let result = CollectionBuilder::new()
+            .schema(input_schema())
+            .env(&Vec::new())
+            .input(&input)
+            .args(vec!["app_name", "-p", "@missing.json"])
+            .build();
+
+        // then
+        assert_eq!(result.err(), Some(Error::Input {
+            name: "payload".to_string(),
+            source: "missing.json".to_string(),
+            message: "not found".to_string(),
+        }));
+    }
+
+    #[test]
+    fn should_validate_values_from_environment() {
+        // given
+        let env = vec![("MYTOOL_PORT", "99999")];
+
+        // when
+        let result = Collection::builder()
+            .schema(Schema::builder().env_prefix("MYTOOL").option::<i64>('p', "port").max(65535).build().unwrap())
+            .env(&env)
+            .args(vec!["app_name"])
+            .build();
+
+        // then
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Invalid value for port: 99999, must be at most 65535".to_string()),
+        );
+    }
+
+    #[test]
+    fn should_validate_values_read_from_input() {
+        // given
+        let input = FakeInput { stdin: "0123456789abc\n" };
+        let schema = Schema::builder()
+            .option::<String>('t', "token").read_input().min_length(12)
+            .build()
+            .unwrap();
+
+        // when
+        let collection = Collection::builder()
+            .schema(schema)
+            .env(&Vec::new())
+            .input(&input)
+            .args(vec!["app_name", "-t", "-"])
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(collection.get_str("token"), Some("0123456789abc"));
+    }
+}
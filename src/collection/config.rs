@@ -0,0 +1,245 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigValue {
+    key: String,
+    value: String,
+    line: usize,
+}
+
+impl ConfigValue {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    path: Option<PathBuf>,
+    values: Vec<ConfigValue>,
+}
+
+impl Config {
+    pub fn from_ini_str(source: &str) -> Result<Self, ConfigError> {
+        let mut values = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') {
+                return Err(ConfigError::Parse {
+                    line: line_number,
+                    message: "Sections are not supported".to_string(),
+                });
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ConfigError::Parse {
+                    line: line_number,
+                    message: "Expected key = value".to_string(),
+                });
+            };
+
+            values.push(ConfigValue {
+                key: key.trim().to_string(),
+                value: unquote(value.trim()).to_string(),
+                line: line_number,
+            });
+        }
+
+        Ok(Self {
+            path: None,
+            values,
+        })
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, ConfigError> {
+        use std::collections::BTreeMap;
+        use toml::{Spanned, Value};
+
+        let line_of = |offset: usize| source[..offset.min(source.len())].matches('\n').count() + 1;
+        let table: BTreeMap<String, Spanned<Value>> = toml::from_str(source).map_err(|err| ConfigError::Parse {
+            line: line_of(err.span().map_or(0, |span| span.start)),
+            message: err.message().to_string(),
+        })?;
+
+        let mut values = Vec::new();
+        for (key, spanned) in table {
+            let line = line_of(spanned.span().start);
+            let value = match spanned.into_inner() {
+                Value::String(val) => val,
+                Value::Integer(val) => val.to_string(),
+                Value::Boolean(val) => val.to_string(),
+                _ => return Err(ConfigError::Parse {
+                    line,
+                    message: format!("Unsupported value for {key}"),
+                }),
+            };
+            values.push(ConfigValue {
+                key,
+                value,
+                line,
+            });
+        }
+        values.sort_by_key(|value| value.line);
+
+        Ok(Self {
+            path: None,
+            values,
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|err| ConfigError::Io {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+
+        #[cfg(feature = "toml")]
+        let config = if path.extension().is_some_and(|ext| ext == "toml") {
+            Self::from_toml_str(&source)?
+        } else {
+            Self::from_ini_str(&source)?
+        };
+        #[cfg(not(feature = "toml"))]
+        let config = Self::from_ini_str(&source)?;
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            ..config
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|value| value.key == key)
+            .map(|value| value.value.as_str())
+    }
+
+    pub fn values(&self) -> &[ConfigValue] {
+        &self.values
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        message: String,
+    },
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, message } => write!(f, "Cannot read {}: {message}", path.display()),
+            ConfigError::Parse { line, message } => write!(f, "{message} at line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_ini() {
+        // given
+        let source = "# comment\ncount = 3\n\n; other comment\noutput = \"out file.txt\"\nverbose=true\n";
+
+        // when
+        let config = Config::from_ini_str(source).unwrap();
+
+        // then
+        assert_eq!(config.get("count"), Some("3"));
+        assert_eq!(config.get("output"), Some("out file.txt"));
+        assert_eq!(config.get("verbose"), Some("true"));
+        assert_eq!(config.values()[1].line(), 5);
+    }
+
+    #[test]
+    fn should_report_line_of_invalid_ini() {
+        // given
+        let source = "count = 3\noutput\n";
+
+        // when
+        let error = Config::from_ini_str(source).unwrap_err();
+
+        // then
+        assert_eq!(error, ConfigError::Parse { line: 2, message: "Expected key = value".to_string() });
+    }
+
+    #[test]
+    fn should_report_missing_file() {
+        // when
+        let error = Config::from_file("/nonexistent/args.ini").unwrap_err();
+
+        // then
+        assert!(matches!(error, ConfigError::Io { .. }));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn should_parse_toml() {
+        // given
+        let source = "count = 3\noutput = \"out.txt\"\nverbose = true\n";
+
+        // when
+        let config = Config::from_toml_str(source).unwrap();
+
+        // then
+        assert_eq!(config.get("count"), Some("3"));
+        assert_eq!(config.get("output"), Some("out.txt"));
+        assert_eq!(config.get("verbose"), Some("true"));
+        assert_eq!(config.values()[2].line(), 3);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn should_reject_nested_toml_values() {
+        // given
+        let source = "count = 3\nlist = [1, 2]\n";
+
+        // when
+        let error = Config::from_toml_str(source).unwrap_err();
+
+        // then
+        assert_eq!(error, ConfigError::Parse { line: 2, message: "Unsupported value for list".to_string() });
+    }
+}
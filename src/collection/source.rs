@@ -0,0 +1,40 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Source {
+    Default,
+    Config,
+    Env,
+    Args,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Origin {
+    Default,
+    Config {
+        path: Option<PathBuf>,
+        line: usize,
+    },
+    Env(String),
+    Args {
+        index: usize,
+        range: Range<usize>,
+    },
+    ResponseFile {
+        index: usize,
+        path: PathBuf,
+        line: usize,
+    },
+}
+
+impl Origin {
+    pub fn source(&self) -> Source {
+        match self {
+            Origin::Default => Source::Default,
+            Origin::Config { .. } => Source::Config,
+            Origin::Env(_) => Source::Env,
+            Origin::Args { .. } | Origin::ResponseFile { .. } => Source::Args,
+        }
+    }
+}
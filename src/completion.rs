@@ -0,0 +1,131 @@
+use crate::schema::argument::ArgumentType;
+use crate::schema::entry::Entry;
+use crate::schema::path::PathConstraint;
+use crate::schema::Schema;
+
+mod bash;
+pub mod dynamic;
+mod fish;
+mod zsh;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+/// Renders a completion script for `program` that completes the options,
+/// choices, paths and subcommands described by `schema`.
+pub fn generate(schema: &Schema, program: &str, shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash::generate(schema, program),
+        Shell::Zsh => zsh::generate(schema, program),
+        Shell::Fish => fish::generate(schema, program),
+    }
+}
+
+// What can be completed as the value of an option.
+enum ValueHint<'a> {
+    Any,
+    Choices(&'a [String]),
+    File,
+    Dir,
+}
+
+fn value_hint(entry: &Entry) -> Option<ValueHint<'_>> {
+    match entry.arg_type() {
+        ArgumentType::Bool => None,
+        ArgumentType::Str if !entry.choices().is_empty() => Some(ValueHint::Choices(entry.choices())),
+        ArgumentType::Path if entry.path_constraints().contains(&PathConstraint::IsDir) => Some(ValueHint::Dir),
+        ArgumentType::Path => Some(ValueHint::File),
+        ArgumentType::Str | ArgumentType::Int | ArgumentType::OsStr => Some(ValueHint::Any),
+    }
+}
+
+// Visits the schema and every nested subcommand, depth first, with the
+// path of subcommand names leading to it.
+fn walk<'a>(schema: &'a Schema, path: &mut Vec<&'a str>, visit: &mut dyn FnMut(&[&'a str], &'a Schema)) {
+    visit(path, schema);
+    for (name, subcommand) in schema.subcommands() {
+        path.push(name);
+        walk(subcommand, path, visit);
+        path.pop();
+    }
+}
+
+fn identifier(program: &str) -> String {
+    program
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        let remote = Schema::builder()
+            .flag('v', "verbose").help("Show remote URLs")
+            .option::<String>('n', "name").help("Name of the remote")
+            .build()
+            .unwrap();
+
+        Schema::builder()
+            .flag('q', "quiet").alias("silent").help("Print nothing")
+            .option::<String>('c', "color").choices(&["always", "auto", "never"]).help("When to use colors")
+            .option::<std::path::PathBuf>('o', "output").help("Write to this file")
+            .option::<std::path::PathBuf>('C', "directory").constraint(PathConstraint::IsDir).help("Run as if started in this directory")
+            .option::<i64>('j', "jobs").help("Number of jobs [default: 1]")
+            .subcommand("remote", remote)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_generate_bash_completion() {
+        // when
+        let script = generate(&schema(), "my-tool", Shell::Bash);
+
+        // then
+        assert_eq!(script, include_str!("completion/golden/my-tool.bash"));
+    }
+
+    #[test]
+    fn should_generate_zsh_completion() {
+        // when
+        let script = generate(&schema(), "my-tool", Shell::Zsh);
+
+        // then
+        assert_eq!(script, include_str!("completion/golden/my-tool.zsh"));
+    }
+
+    #[test]
+    fn should_generate_fish_completion() {
+        // when
+        let script = generate(&schema(), "my-tool", Shell::Fish);
+
+        // then
+        assert_eq!(script, include_str!("completion/golden/my-tool.fish"));
+    }
+}
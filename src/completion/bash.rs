@@ -0,0 +1,83 @@
+use crate::completion::{identifier, value_hint, walk, ValueHint};
+use crate::schema::builder::spellings_of;
+use crate::schema::Schema;
+use std::fmt::Write;
+
+pub(crate) fn generate(schema: &Schema, program: &str) -> String {
+    let function = identifier(program);
+    let mut commands = Vec::new();
+    walk(schema, &mut Vec::new(), &mut |path, schema| {
+        commands.push((command_name(&function, path), schema));
+    });
+
+    let mut script = String::new();
+    writeln!(script, "_{function}() {{").unwrap();
+    writeln!(script, "    local cur prev cmd i").unwrap();
+    writeln!(script, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").unwrap();
+    writeln!(script, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"").unwrap();
+    writeln!(script, "    cmd=\"{function}\"").unwrap();
+
+    if schema.subcommands().next().is_some() {
+        writeln!(script).unwrap();
+        writeln!(script, "    for ((i = 1; i < COMP_CWORD; i++)); do").unwrap();
+        writeln!(script, "        case \"${{cmd}}__${{COMP_WORDS[i]}}\" in").unwrap();
+        for (command, schema) in &commands {
+            for (name, _) in schema.subcommands() {
+                writeln!(script, "            {command}__{name})").unwrap();
+                writeln!(script, "                cmd=\"{command}__{}\"", identifier(name)).unwrap();
+                writeln!(script, "                ;;").unwrap();
+            }
+        }
+        writeln!(script, "        esac").unwrap();
+        writeln!(script, "    done").unwrap();
+    }
+
+    writeln!(script).unwrap();
+    writeln!(script, "    case \"${{cmd}}\" in").unwrap();
+    for (command, schema) in &commands {
+        writeln!(script, "        {command})").unwrap();
+        write_command(&mut script, schema);
+        writeln!(script, "            ;;").unwrap();
+    }
+    writeln!(script, "    esac").unwrap();
+    writeln!(script, "}}").unwrap();
+    writeln!(script).unwrap();
+    writeln!(script, "complete -F _{function} {program}").unwrap();
+
+    script
+}
+
+fn write_command(script: &mut String, schema: &Schema) {
+    let valued: Vec<_> = schema.entries()
+        .iter()
+        .filter_map(|entry| Some((entry, value_hint(entry)?)))
+        .collect();
+
+    if !valued.is_empty() {
+        writeln!(script, "            case \"${{prev}}\" in").unwrap();
+        for (entry, hint) in valued {
+            let reply = match hint {
+                ValueHint::Any => "()".to_string(),
+                ValueHint::Choices(choices) => format!("($(compgen -W \"{}\" -- \"${{cur}}\"))", choices.join(" ")),
+                ValueHint::File => "($(compgen -f -- \"${cur}\"))".to_string(),
+                ValueHint::Dir => "($(compgen -d -- \"${cur}\"))".to_string(),
+            };
+            writeln!(script, "                {})", spellings_of(entry).join("|")).unwrap();
+            writeln!(script, "                    COMPREPLY={reply}").unwrap();
+            writeln!(script, "                    return 0").unwrap();
+            writeln!(script, "                    ;;").unwrap();
+        }
+        writeln!(script, "            esac").unwrap();
+    }
+
+    let words: Vec<String> = schema.entries()
+        .iter()
+        .flat_map(spellings_of)
+        .chain(schema.subcommands().map(|(name, _)| name.to_string()))
+        .collect();
+    writeln!(script, "            COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))", words.join(" ")).unwrap();
+}
+
+fn command_name(function: &str, path: &[&str]) -> String {
+    path.iter().fold(function.to_string(), |command, name| format!("{command}__{}", identifier(name)))
+}
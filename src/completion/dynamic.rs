@@ -0,0 +1,387 @@
+use crate::completion::{identifier, value_hint, Shell, ValueHint};
+use crate::collection::token_value;
+use crate::env::Env;
+use crate::schema::argument::ArgumentType;
+use crate::schema::entry::Entry;
+use crate::schema::value::Value;
+use crate::schema::builder::spellings_of;
+use crate::schema::Schema;
+use crate::token::parser::TokenParser;
+use crate::token::Token;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+const COMPLETE_VAR: &str = "COMPLETE";
+const INDEX_VAR: &str = "COMPLETE_INDEX";
+const SUBCOMMAND: &str = "complete";
+
+/// Supplies the candidates for the value of an entry, given what has been
+/// typed so far.
+#[derive(Clone)]
+pub struct Candidates(Arc<CandidatesFn>);
+
+type CandidatesFn = dyn Fn(&Context) -> Vec<String> + Send + Sync;
+
+impl Candidates {
+    pub fn new(candidates: impl Fn(&Context) -> Vec<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(candidates))
+    }
+}
+
+impl PartialEq for Candidates {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for Candidates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Candidates")
+    }
+}
+
+pub struct Context {
+    prefix: String,
+    values: HashMap<String, Value>,
+}
+
+impl Context {
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The value given earlier on the command line for an entry of the
+    /// command being completed.
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+}
+
+/// Answers a completion request when the program was invoked by one of the
+/// scripts printed by `register`, either through `COMPLETE=<shell>` or the
+/// hidden `complete` subcommand. Returns `None` for a regular invocation.
+///
+/// A request carries the words of the command line after `--`, with the
+/// cursor given by `COMPLETE_INDEX` or, for the subcommand, as the argument
+/// before `--`. Without words, `COMPLETE=<shell>` prints the registration
+/// script of that shell instead.
+pub fn respond(schema: &Schema, args: &[String], env: &dyn Env) -> Option<String> {
+    let program = args.first()?;
+    let program = Path::new(program).file_name()?.to_str()?;
+
+    if let Some(shell) = env.var(COMPLETE_VAR) {
+        let shell = Shell::from_name(&shell)?;
+        let Some(words) = words_after_separator(&args[1..]) else {
+            return Some(register(program, shell));
+        };
+        let cursor = env.var(INDEX_VAR).and_then(|index| index.parse().ok());
+        return Some(output(complete(schema, words, cursor.unwrap_or(words.len().saturating_sub(1)))));
+    }
+
+    if args.get(1).map(String::as_str) != Some(SUBCOMMAND) || schema.subcommand(SUBCOMMAND).is_some() {
+        return None;
+    }
+    let cursor = args.get(2)?.parse().ok()?;
+    let words = words_after_separator(&args[3..])?;
+
+    Some(output(complete(schema, words, cursor)))
+}
+
+/// Candidates for `words[cursor]`, where `words[0]` is the program name.
+///
+/// A word of the form `--opt=partial` completes the value of `--opt`, with
+/// candidates spelled out as `--opt=value`. Bash passes it as the separate
+/// words `--opt`, `=` and `partial`, or just `--opt` and `=` with the cursor
+/// on the `=`, for which bare values are returned.
+pub fn complete(schema: &Schema, words: &[String], cursor: usize) -> Vec<String> {
+    let cursor = cursor.clamp(1, words.len().max(1));
+    let current = words.get(cursor).map(String::as_str).unwrap_or("");
+    let mut end = cursor.min(words.len());
+    let mut prefix = current;
+    let mut attached = None;
+    if let Some((option, value)) = current.split_once('=').filter(|(option, _)| option.starts_with("--")) {
+        attached = Some((option, format!("{option}=")));
+        prefix = value;
+    } else if current == "=" && words[cursor - 1].starts_with("--") {
+        attached = Some((words[cursor - 1].as_str(), String::new()));
+        prefix = "";
+        end = cursor - 1;
+    } else if cursor >= 3 && words[cursor - 1] == "=" && words[cursor - 2].starts_with("--") {
+        attached = Some((words[cursor - 2].as_str(), String::new()));
+        end = cursor - 2;
+    }
+    let preceding = words[..end].iter().map(String::as_str).collect();
+
+    let Ok(tokens) = TokenParser::new().args(preceding).schema(schema.clone()).try_collect() else {
+        return Vec::new();
+    };
+
+    let mut active = schema;
+    let mut values = HashMap::new();
+    let mut pending = None;
+    for index in 0..tokens.size() {
+        pending = None;
+        match tokens.get(index) {
+            Token::Subcommand(name) => {
+                active = active.subcommand(name).expect("Parsed subcommands are defined");
+                values.clear();
+            },
+            Token::Argument(ArgumentType::Bool, name) => {
+                values.insert(name.clone(), Value::Bool(true));
+            },
+            Token::Argument(_, name) => match (index + 1 < tokens.size()).then(|| tokens.get(index + 1)).and_then(token_value) {
+                Some(value) => {
+                    values.insert(name.clone(), value);
+                },
+                None => pending = Some(name.clone()),
+            },
+            _ => (),
+        }
+    }
+
+    let context = Context {
+        prefix: prefix.to_string(),
+        values,
+    };
+
+    if let Some((option, spelled)) = attached {
+        return match active.resolve_abbreviated(option) {
+            Ok(Some(entry)) if *entry.arg_type() != ArgumentType::Bool => value_candidates(entry, &context)
+                .into_iter()
+                .map(|candidate| format!("{spelled}{candidate}"))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    match pending.and_then(|name| active.entry(&name)) {
+        Some(entry) => value_candidates(entry, &context),
+        None => argument_candidates(active, prefix),
+    }
+}
+
+fn value_candidates(entry: &Entry, context: &Context) -> Vec<String> {
+    let candidates = match (entry.candidates(), value_hint(entry)) {
+        (Some(candidates), _) => (candidates.0)(context),
+        (None, Some(ValueHint::Choices(choices))) => choices.to_vec(),
+        (None, Some(ValueHint::File)) => paths(context.prefix(), false),
+        (None, Some(ValueHint::Dir)) => paths(context.prefix(), true),
+        (None, _) => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(context.prefix()))
+        .collect()
+}
+
+fn argument_candidates(schema: &Schema, prefix: &str) -> Vec<String> {
+    let options = schema.entries().iter().flat_map(spellings_of);
+    let subcommands = schema.subcommands().map(|(name, _)| name.to_string());
+
+    subcommands
+        .filter(|_| !prefix.starts_with('-'))
+        .chain(options)
+        .filter(|candidate| candidate.starts_with(prefix))
+        .collect()
+}
+
+fn paths(prefix: &str, dirs_only: bool) -> Vec<String> {
+    let (dir, start) = match prefix.rfind('/') {
+        Some(index) => (&prefix[..=index], &prefix[index + 1..]),
+        None => ("", prefix),
+    };
+    let Ok(entries) = std::fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let is_dir = entry.file_type().ok()?.is_dir();
+            if !name.starts_with(start) || (name.starts_with('.') && !start.starts_with('.')) || (dirs_only && !is_dir) {
+                return None;
+            }
+            Some(format!("{dir}{name}{}", if is_dir { "/" } else { "" }))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn words_after_separator(args: &[String]) -> Option<&[String]> {
+    match args.split_first() {
+        Some((separator, words)) if separator == "--" => Some(words),
+        _ => None,
+    }
+}
+
+fn output(candidates: Vec<String>) -> String {
+    candidates.into_iter().map(|candidate| candidate + "\n").collect()
+}
+
+/// The script that hooks `program` into the completion of `shell`, to be
+/// sourced from the output of `COMPLETE=<shell> program`.
+pub fn register(program: &str, shell: Shell) -> String {
+    let function = identifier(program);
+
+    match shell {
+        Shell::Bash => format!(
+            "_{function}() {{\n    \
+                local IFS=$'\\n'\n    \
+                COMPREPLY=($(COMPLETE=bash COMPLETE_INDEX=\"${{COMP_CWORD}}\" {program} -- \"${{COMP_WORDS[@]}}\"))\n\
+            }}\n\
+            \n\
+            complete -o default -F _{function} {program}\n"
+        ),
+        Shell::Zsh => format!(
+            "#compdef {program}\n\
+            \n\
+            _{function}() {{\n    \
+                local -a candidates\n    \
+                candidates=(\"${{(@f)$(COMPLETE=zsh COMPLETE_INDEX=$((CURRENT - 1)) {program} -- \"${{words[@]}}\")}}\")\n    \
+                compadd -a candidates\n\
+            }}\n\
+            \n\
+            compdef _{function} {program}\n"
+        ),
+        Shell::Fish => format!(
+            "complete -c {program} -f -a '(COMPLETE=fish COMPLETE_INDEX=(count (commandline -opc)) {program} -- (commandline -opc) (commandline -ct))'\n"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn schema() -> Schema {
+        let remote = Schema::builder()
+            .option::<String>('n', "name")
+            .candidates(|context| match context.value("url") {
+                Some(Value::Str(url)) if url.contains("github") => vec!["origin".to_string(), "upstream".to_string()],
+                _ => vec!["origin".to_string()],
+            })
+            .option::<String>('u', "url")
+            .build()
+            .unwrap();
+
+        Schema::builder()
+            .flag('q', "quiet")
+            .option::<String>('c', "color").choices(&["always", "auto", "never"])
+            .option::<PathBuf>('o', "output")
+            .subcommand("remote", remote)
+            .build()
+            .unwrap()
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn should_complete_options_and_subcommands() {
+        // when
+        let all = complete(&schema(), &words(&["tool", ""]), 1);
+        let long = complete(&schema(), &words(&["tool", "-q", "--c"]), 2);
+        let subcommands = complete(&schema(), &words(&["tool", "re"]), 1);
+
+        // then
+        assert_eq!(all, words(&["remote", "-q", "--quiet", "-c", "--color", "-o", "--output"]));
+        assert_eq!(long, words(&["--color"]));
+        assert_eq!(subcommands, words(&["remote"]));
+    }
+
+    #[test]
+    fn should_complete_choices_of_pending_option() {
+        // when
+        let candidates = complete(&schema(), &words(&["tool", "--color", "a", "-q"]), 2);
+
+        // then
+        assert_eq!(candidates, words(&["always", "auto"]));
+    }
+
+    #[test]
+    fn should_pass_earlier_values_to_candidate_hooks() {
+        // when
+        let plain = complete(&schema(), &words(&["tool", "remote", "-n", ""]), 3);
+        let github = complete(&schema(), &words(&["tool", "remote", "-u", "github.com/a/b", "-n", "u"]), 5);
+
+        // then
+        assert_eq!(plain, words(&["origin"]));
+        assert_eq!(github, words(&["upstream"]));
+    }
+
+    #[test]
+    fn should_complete_paths() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("logs")).unwrap();
+        std::fs::write(dir.path().join("out.txt"), "").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "").unwrap();
+        let prefix = format!("{}/o", dir.path().display());
+
+        // when
+        let candidates = complete(&schema(), &words(&["tool", "-o", &prefix]), 2);
+
+        // then
+        let expected = ["other.txt", "out.txt"].map(|name| format!("{}/{name}", dir.path().display()));
+        assert_eq!(candidates, expected.to_vec());
+    }
+
+    #[test]
+    fn should_respond_to_complete_variable() {
+        // given
+        let env = vec![("COMPLETE", "bash"), ("COMPLETE_INDEX", "2")];
+
+        // when
+        let response = respond(&schema(), &words(&["/usr/bin/tool", "--", "tool", "--color", "n"]), &env);
+        let registration = respond(&schema(), &words(&["/usr/bin/tool"]), &env);
+
+        // then
+        assert_eq!(response, Some("never\n".to_string()));
+        assert_eq!(registration, Some(register("tool", Shell::Bash)));
+    }
+
+    #[test]
+    fn should_respond_to_complete_subcommand() {
+        // when
+        let response = respond(&schema(), &words(&["tool", "complete", "1", "--", "tool", "--q"]), &Vec::new());
+        let regular = respond(&schema(), &words(&["tool", "-q"]), &Vec::new());
+
+        // then
+        assert_eq!(response, Some("--quiet\n".to_string()));
+        assert_eq!(regular, None);
+    }
+
+    #[test]
+    fn should_complete_empty_word_at_fish_cursor() {
+        // given
+        let env = vec![("COMPLETE", "fish"), ("COMPLETE_INDEX", "2")];
+
+        // when
+        let response = respond(&schema(), &words(&["tool", "--", "tool", "--color"]), &env);
+
+        // then
+        assert_eq!(response, Some("always\nauto\nnever\n".to_string()));
+    }
+
+    #[test]
+    fn should_complete_values_attached_to_long_options() {
+        // when
+        let attached = complete(&schema(), &words(&["tool", "-q", "--color=a"]), 2);
+        let split = complete(&schema(), &words(&["tool", "--color", "=", "n"]), 3);
+        let separator = complete(&schema(), &words(&["tool", "--color", "="]), 2);
+        let flag = complete(&schema(), &words(&["tool", "--quiet="]), 1);
+
+        // then
+        assert_eq!(attached, words(&["--color=always", "--color=auto"]));
+        assert_eq!(split, words(&["never"]));
+        assert_eq!(separator, words(&["always", "auto", "never"]));
+        assert_eq!(flag, Vec::<String>::new());
+    }
+}
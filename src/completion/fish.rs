@@ -0,0 +1,59 @@
+use crate::completion::{value_hint, walk, ValueHint};
+use crate::schema::builder::spellings_of;
+use crate::schema::entry::Entry;
+use crate::schema::Schema;
+use std::fmt::Write;
+
+pub(crate) fn generate(schema: &Schema, program: &str) -> String {
+    let mut script = String::new();
+
+    walk(schema, &mut Vec::new(), &mut |path, schema| {
+        // Fish only tells which subcommands were seen, so nested commands
+        // are told apart by the last name on their path.
+        let condition = match path.last() {
+            None if schema.subcommands().next().is_some() => Some("__fish_use_subcommand".to_string()),
+            None => None,
+            Some(name) => Some(format!("__fish_seen_subcommand_from {name}")),
+        };
+        let prefix = match &condition {
+            Some(condition) => format!("complete -c {program} -n '{condition}'"),
+            None => format!("complete -c {program}"),
+        };
+
+        for entry in schema.entries() {
+            writeln!(script, "{prefix}{}", option(entry)).unwrap();
+        }
+        for (name, _) in schema.subcommands() {
+            writeln!(script, "{prefix} -f -a {name}").unwrap();
+        }
+    });
+
+    script
+}
+
+fn option(entry: &Entry) -> String {
+    let mut line = String::new();
+
+    for spelling in spellings_of(entry) {
+        match spelling.strip_prefix("--") {
+            Some(long) => write!(line, " -l {long}").unwrap(),
+            None => write!(line, " -s {}", &spelling[1..]).unwrap(),
+        }
+    }
+    match value_hint(entry) {
+        None => (),
+        Some(ValueHint::Any) => line.push_str(" -r -f"),
+        Some(ValueHint::Choices(choices)) => write!(line, " -r -f -a '{}'", quote(&choices.join(" "))).unwrap(),
+        Some(ValueHint::File) => line.push_str(" -r -F"),
+        Some(ValueHint::Dir) => line.push_str(" -r -f -a '(__fish_complete_directories)'"),
+    }
+    if let Some(help) = entry.help() {
+        write!(line, " -d '{}'", quote(help)).unwrap();
+    }
+
+    line
+}
+
+fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
@@ -0,0 +1,49 @@
+_my_tool() {
+    local cur prev cmd i
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="my_tool"
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd}__${COMP_WORDS[i]}" in
+            my_tool__remote)
+                cmd="my_tool__remote"
+                ;;
+        esac
+    done
+
+    case "${cmd}" in
+        my_tool)
+            case "${prev}" in
+                -c|--color)
+                    COMPREPLY=($(compgen -W "always auto never" -- "${cur}"))
+                    return 0
+                    ;;
+                -o|--output)
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+                -C|--directory)
+                    COMPREPLY=($(compgen -d -- "${cur}"))
+                    return 0
+                    ;;
+                -j|--jobs)
+                    COMPREPLY=()
+                    return 0
+                    ;;
+            esac
+            COMPREPLY=($(compgen -W "-q --quiet --silent -c --color -o --output -C --directory -j --jobs remote" -- "${cur}"))
+            ;;
+        my_tool__remote)
+            case "${prev}" in
+                -n|--name)
+                    COMPREPLY=()
+                    return 0
+                    ;;
+            esac
+            COMPREPLY=($(compgen -W "-v --verbose -n --name" -- "${cur}"))
+            ;;
+    esac
+}
+
+complete -F _my_tool my-tool
@@ -0,0 +1,8 @@
+complete -c my-tool -n '__fish_use_subcommand' -s q -l quiet -l silent -d 'Print nothing'
+complete -c my-tool -n '__fish_use_subcommand' -s c -l color -r -f -a 'always auto never' -d 'When to use colors'
+complete -c my-tool -n '__fish_use_subcommand' -s o -l output -r -F -d 'Write to this file'
+complete -c my-tool -n '__fish_use_subcommand' -s C -l directory -r -f -a '(__fish_complete_directories)' -d 'Run as if started in this directory'
+complete -c my-tool -n '__fish_use_subcommand' -s j -l jobs -r -f -d 'Number of jobs [default: 1]'
+complete -c my-tool -n '__fish_use_subcommand' -f -a remote
+complete -c my-tool -n '__fish_seen_subcommand_from remote' -s v -l verbose -d 'Show remote URLs'
+complete -c my-tool -n '__fish_seen_subcommand_from remote' -s n -l name -r -f -d 'Name of the remote'
@@ -0,0 +1,99 @@
+use crate::completion::{identifier, value_hint, walk, ValueHint};
+use crate::schema::entry::Entry;
+use crate::schema::builder::spellings_of;
+use crate::schema::Schema;
+use std::fmt::Write;
+
+pub(crate) fn generate(schema: &Schema, program: &str) -> String {
+    let function = identifier(program);
+    let mut script = String::new();
+    writeln!(script, "#compdef {program}").unwrap();
+
+    walk(schema, &mut Vec::new(), &mut |path, schema| {
+        let command = path.iter().fold(format!("_{function}"), |command, name| {
+            format!("{command}__{}", identifier(name))
+        });
+        writeln!(script).unwrap();
+        write_function(&mut script, &command, schema);
+    });
+
+    writeln!(script).unwrap();
+    writeln!(script, "_{function} \"$@\"").unwrap();
+
+    script
+}
+
+fn write_function(script: &mut String, command: &str, schema: &Schema) {
+    let has_subcommands = schema.subcommands().next().is_some();
+    let mut specs: Vec<String> = schema.entries().iter().map(spec).collect();
+
+    writeln!(script, "{command}() {{").unwrap();
+    if has_subcommands {
+        writeln!(script, "    local context state state_descr line").unwrap();
+        writeln!(script, "    typeset -A opt_args").unwrap();
+        writeln!(script).unwrap();
+        specs.push("'1: :->command'".to_string());
+        specs.push("'*:: :->args'".to_string());
+    }
+    match specs.as_slice() {
+        [] => writeln!(script, "    _arguments").unwrap(),
+        _ => {
+            let flags = if has_subcommands { " -C" } else { "" };
+            writeln!(script, "    _arguments{flags} \\").unwrap();
+            writeln!(script, "        {}", specs.join(" \\\n        ")).unwrap();
+        },
+    }
+
+    if has_subcommands {
+        let names: Vec<String> = schema.subcommands().map(|(name, _)| format!("'{}'", quote(name))).collect();
+        writeln!(script).unwrap();
+        writeln!(script, "    case $state in").unwrap();
+        writeln!(script, "        command)").unwrap();
+        writeln!(script, "            _values 'command' {}", names.join(" ")).unwrap();
+        writeln!(script, "            ;;").unwrap();
+        writeln!(script, "        args)").unwrap();
+        writeln!(script, "            case $line[1] in").unwrap();
+        for (name, _) in schema.subcommands() {
+            writeln!(script, "                {name})").unwrap();
+            writeln!(script, "                    {command}__{}", identifier(name)).unwrap();
+            writeln!(script, "                    ;;").unwrap();
+        }
+        writeln!(script, "            esac").unwrap();
+        writeln!(script, "            ;;").unwrap();
+        writeln!(script, "    esac").unwrap();
+    }
+    writeln!(script, "}}").unwrap();
+}
+
+// An `_arguments` spec such as `'(-o --output)'{-o,--output}'[help]:output:_files'`.
+fn spec(entry: &Entry) -> String {
+    let spellings = spellings_of(entry);
+    let help = entry.help().map(|help| format!("[{}]", escape(help))).unwrap_or_default();
+    let name = quote(entry.name()).replace(':', "\\:");
+    let value = match value_hint(entry) {
+        None => String::new(),
+        Some(hint) => {
+            let action = match hint {
+                ValueHint::Any => String::new(),
+                ValueHint::Choices(choices) => format!("({})", choices.join(" ")),
+                ValueHint::File => "_files".to_string(),
+                ValueHint::Dir => "_files -/".to_string(),
+            };
+            format!(":{name}:{action}")
+        },
+    };
+    let exclusions = spellings.join(" ");
+
+    match spellings.as_slice() {
+        [spelling] => format!("'({exclusions}){spelling}{help}{value}'"),
+        _ => format!("'({exclusions})'{{{}}}'{help}{value}'", spellings.join(",")),
+    }
+}
+
+fn escape(text: &str) -> String {
+    quote(text).replace('[', "\\[").replace(']', "\\]")
+}
+
+fn quote(text: &str) -> String {
+    text.replace('\'', "'\\''")
+}
@@ -0,0 +1,70 @@
+use crate::schema::argument::ArgumentType;
+use crate::schema::entry::Entry;
+use crate::schema::Schema;
+use crate::sensitive::REDACTED;
+
+pub mod man;
+pub mod markdown;
+
+const DEFAULT_NAME: &str = "program";
+
+fn program_name(schema: &Schema) -> &str {
+    schema.name().unwrap_or(DEFAULT_NAME)
+}
+
+// The placeholder shown for the value of an option, `None` for flags.
+fn value_name(entry: &Entry) -> Option<String> {
+    match entry.arg_type() {
+        ArgumentType::Bool => None,
+        _ => Some(entry.name().to_uppercase().replace('-', "_")),
+    }
+}
+
+fn default_text(entry: &Entry) -> Option<String> {
+    let default = entry.default()?;
+
+    Some(match entry.is_sensitive() {
+        true => REDACTED.to_string(),
+        false => default.to_string(),
+    })
+}
+
+// Visits every subcommand, depth first, with its full command line such as
+// `git remote add`.
+fn walk_subcommands<'a>(schema: &'a Schema, command: &str, visit: &mut dyn FnMut(&str, &'a Schema)) {
+    for (name, subcommand) in schema.subcommands() {
+        let command = format!("{command} {name}");
+        visit(&command, subcommand);
+        walk_subcommands(subcommand, &command, visit);
+    }
+}
+
+#[cfg(test)]
+fn example() -> Schema {
+    use crate::schema::path::PathConstraint;
+    use std::path::PathBuf;
+
+    let add = Schema::builder()
+        .description("Add a remote")
+        .option::<String>('u', "url").required().help("URL of the remote")
+        .build()
+        .unwrap();
+    let remote = Schema::builder()
+        .description("Manage remotes")
+        .flag('v', "verbose").help("Show remote URLs")
+        .subcommand("add", add)
+        .build()
+        .unwrap();
+
+    Schema::builder()
+        .name("my-tool")
+        .description("Does things with remotes")
+        .env_prefix("MY_TOOL")
+        .flag('q', "quiet").alias("silent").help("Print nothing")
+        .option::<String>('c', "color").choices(&["always", "auto", "never"]).default("auto").help("When to use colors")
+        .option::<PathBuf>('C', "directory").constraint(PathConstraint::IsDir).help("Run as if started in this directory")
+        .option::<String>('t', "token").sensitive().default("hunter2").env("TOKEN").help("API token")
+        .subcommand("remote", remote)
+        .build()
+        .unwrap()
+}
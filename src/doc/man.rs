@@ -0,0 +1,148 @@
+use crate::doc::{default_text, program_name, value_name, walk_subcommands};
+use crate::schema::builder::spellings_of;
+use crate::schema::entry::Entry;
+use crate::schema::Schema;
+use std::fmt::Write;
+
+/// Renders `schema` as a `man(7)` page in section 1.
+pub fn render(schema: &Schema) -> String {
+    let program = program_name(schema);
+    let mut page = String::new();
+
+    writeln!(page, ".TH {} 1", escape(&program.to_uppercase())).unwrap();
+    writeln!(page, ".SH NAME").unwrap();
+    match schema.description() {
+        Some(description) => writeln!(page, "{} \\- {}", escape(program), escape(description)).unwrap(),
+        None => writeln!(page, "{}", escape(program)).unwrap(),
+    }
+
+    writeln!(page, ".SH SYNOPSIS").unwrap();
+    writeln!(page, "{}", synopsis(program, schema)).unwrap();
+
+    if let Some(description) = schema.description() {
+        writeln!(page, ".SH DESCRIPTION").unwrap();
+        writeln!(page, "{}", text(description)).unwrap();
+    }
+
+    if !schema.entries().is_empty() {
+        writeln!(page, ".SH OPTIONS").unwrap();
+        write_options(&mut page, schema);
+    }
+
+    if schema.subcommands().next().is_some() {
+        writeln!(page, ".SH COMMANDS").unwrap();
+        walk_subcommands(schema, program, &mut |command, subcommand| {
+            writeln!(page, ".SS {}", escape(command)).unwrap();
+            writeln!(page, "{}", synopsis(command, subcommand)).unwrap();
+            if let Some(description) = subcommand.description() {
+                writeln!(page, ".PP").unwrap();
+                writeln!(page, "{}", text(description)).unwrap();
+            }
+            write_options(&mut page, subcommand);
+        });
+    }
+
+    page
+}
+
+fn synopsis(command: &str, schema: &Schema) -> String {
+    let mut synopsis = format!("\\fB{}\\fR", escape(command));
+
+    if !schema.entries().is_empty() {
+        synopsis.push_str(" [\\fIOPTIONS\\fR]");
+    }
+    if schema.subcommands().next().is_some() {
+        synopsis.push_str(" \\fICOMMAND\\fR");
+    }
+    if schema.accepts_positionals() {
+        synopsis.push_str(" [\\fIARGS\\fR]...");
+    }
+    synopsis
+}
+
+fn write_options(page: &mut String, schema: &Schema) {
+    for entry in schema.entries() {
+        writeln!(page, ".TP").unwrap();
+        writeln!(page, "{}", option(entry)).unwrap();
+
+        let mut lines = Vec::new();
+        if let Some(help) = entry.help() {
+            lines.push(text(help));
+        }
+        if entry.is_required() {
+            lines.push("Required.".to_string());
+        }
+        if !entry.choices().is_empty() {
+            lines.push(format!("Possible values: {}", escape(&entry.choices().join(", "))));
+        }
+        if let Some(default) = default_text(entry) {
+            lines.push(format!("Default: {}", escape(&default)));
+        }
+        if let Some(var) = schema.env_var(entry) {
+            lines.push(format!("Environment: \\fB{}\\fR", escape(&var)));
+        }
+        if !lines.is_empty() {
+            writeln!(page, "{}", lines.join("\n.br\n")).unwrap();
+        }
+    }
+}
+
+// `\fB\-c\fR, \fB\-\-color\fR \fICOLOR\fR`
+fn option(entry: &Entry) -> String {
+    let spellings: Vec<String> = spellings_of(entry)
+        .iter()
+        .map(|spelling| format!("\\fB{}\\fR", escape(spelling)))
+        .collect();
+    let value = value_name(entry)
+        .map(|name| format!(" \\fI{name}\\fR"))
+        .unwrap_or_default();
+
+    format!("{}{value}", spellings.join(", "))
+}
+
+// Escapes text that starts a line, where a leading `.` or `'` would be
+// read as a request.
+fn text(text: &str) -> String {
+    escape(text)
+        .lines()
+        .map(|line| match line.starts_with(['.', '\'']) {
+            true => format!("\\&{line}"),
+            false => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e").replace('-', "\\-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::example;
+
+    #[test]
+    fn should_render_man_page() {
+        // when
+        let page = render(&example());
+
+        // then
+        assert_eq!(page, include_str!("golden/my-tool.1"));
+    }
+
+    #[test]
+    fn should_escape_requests_at_line_start() {
+        // given
+        let schema = Schema::builder()
+            .description(".hidden files and 'quotes'\nsecond-line")
+            .build()
+            .unwrap();
+
+        // when
+        let page = render(&schema);
+
+        // then
+        assert!(page.contains(".SH DESCRIPTION\n\\&.hidden files and 'quotes'\nsecond\\-line\n"), "{page}");
+    }
+}
@@ -0,0 +1,137 @@
+use crate::doc::{default_text, program_name, value_name, walk_subcommands};
+use crate::schema::builder::spellings_of;
+use crate::schema::entry::Entry;
+use crate::schema::Schema;
+use std::fmt::Write;
+
+/// Renders `schema` as a Markdown reference: a synopsis, a table of options
+/// and a section per subcommand.
+pub fn render(schema: &Schema) -> String {
+    let program = program_name(schema);
+    let mut document = String::new();
+
+    writeln!(document, "# {program}").unwrap();
+    write_command(&mut document, program, schema);
+    walk_subcommands(schema, program, &mut |command, subcommand| {
+        writeln!(document).unwrap();
+        writeln!(document, "## {command}").unwrap();
+        write_command(&mut document, command, subcommand);
+    });
+
+    document
+}
+
+fn write_command(document: &mut String, command: &str, schema: &Schema) {
+    if let Some(description) = schema.description() {
+        writeln!(document).unwrap();
+        writeln!(document, "{description}").unwrap();
+    }
+
+    writeln!(document).unwrap();
+    writeln!(document, "```").unwrap();
+    writeln!(document, "{}", usage(command, schema)).unwrap();
+    writeln!(document, "```").unwrap();
+
+    if !schema.entries().is_empty() {
+        writeln!(document).unwrap();
+        writeln!(document, "| Option | Type | Default | Environment | Description |").unwrap();
+        writeln!(document, "| --- | --- | --- | --- | --- |").unwrap();
+        for entry in schema.entries() {
+            writeln!(document, "{}", row(schema, entry)).unwrap();
+        }
+    }
+
+    let subcommands: Vec<&str> = schema.subcommands().map(|(name, _)| name).collect();
+    if !subcommands.is_empty() {
+        writeln!(document).unwrap();
+        writeln!(document, "Commands:").unwrap();
+        writeln!(document).unwrap();
+        for (name, subcommand) in schema.subcommands() {
+            match subcommand.description() {
+                Some(description) => writeln!(document, "- `{name}`: {}", inline(description)).unwrap(),
+                None => writeln!(document, "- `{name}`").unwrap(),
+            }
+        }
+    }
+}
+
+fn usage(command: &str, schema: &Schema) -> String {
+    let mut usage = command.to_string();
+
+    if !schema.entries().is_empty() {
+        usage.push_str(" [OPTIONS]");
+    }
+    if schema.subcommands().next().is_some() {
+        usage.push_str(" COMMAND");
+    }
+    if schema.accepts_positionals() {
+        usage.push_str(" [ARGS]...");
+    }
+    usage
+}
+
+fn row(schema: &Schema, entry: &Entry) -> String {
+    let mut option: Vec<String> = spellings_of(entry).iter().map(|spelling| format!("`{spelling}`")).collect();
+    if let Some(value) = value_name(entry) {
+        if let Some(last) = option.last_mut() {
+            *last = format!("{} {value}`", last.trim_end_matches('`'));
+        }
+    }
+
+    let mut description: Vec<String> = entry.help().map(inline).into_iter().collect();
+    if entry.is_required() {
+        description.push("Required.".to_string());
+    }
+    if !entry.choices().is_empty() {
+        let choices: Vec<String> = entry.choices().iter().map(|choice| format!("`{}`", cell(choice))).collect();
+        description.push(format!("Possible values: {}.", choices.join(", ")));
+    }
+
+    let default = default_text(entry).map(|default| format!("`{}`", cell(&default))).unwrap_or_default();
+    let env = schema.env_var(entry).map(|var| format!("`{var}`")).unwrap_or_default();
+
+    format!(
+        "| {} | {} | {default} | {env} | {} |",
+        option.join(", "),
+        entry.arg_type().name(),
+        description.join("<br>"),
+    )
+}
+
+fn inline(text: &str) -> String {
+    cell(text).lines().collect::<Vec<_>>().join("<br>")
+}
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::example;
+
+    #[test]
+    fn should_render_markdown_reference() {
+        // when
+        let document = render(&example());
+
+        // then
+        assert_eq!(document, include_str!("golden/my-tool.md"));
+    }
+
+    #[test]
+    fn should_escape_table_cells() {
+        // given
+        let schema = Schema::builder()
+            .option::<String>('f', "format").help("Either json | table\nor yaml")
+            .build()
+            .unwrap();
+
+        // when
+        let document = render(&schema);
+
+        // then
+        assert!(document.contains("| `-f`, `--format FORMAT` | string |  |  | Either json \\| table<br>or yaml |"), "{document}");
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+const POSIXLY_CORRECT: &str = "POSIXLY_CORRECT";
+
+pub trait Env {
+    fn var(&self, name: &str) -> Option<String>;
+}
+
+/// Whether the environment asks for POSIX argument ordering regardless of
+/// the schema, as GNU tools do.
+pub fn posixly_correct(env: &dyn Env) -> bool {
+    env.var(POSIXLY_CORRECT).is_some()
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct ProcessEnv;
+
+impl Env for ProcessEnv {
+    fn var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+impl Env for HashMap<String, String> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl Env for Vec<(&str, &str)> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, val)| val.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_read_injected_variables() {
+        // given
+        let env = vec![("MYTOOL_OUTPUT", "out.txt")];
+
+        // then
+        assert_eq!(env.var("MYTOOL_OUTPUT"), Some("out.txt".to_string()));
+        assert_eq!(env.var("MYTOOL_INPUT"), None);
+    }
+
+    #[test]
+    fn should_read_variables_from_map() {
+        // given
+        let env = HashMap::from([("COUNT".to_string(), "3".to_string())]);
+
+        // then
+        assert_eq!(env.var("COUNT"), Some("3".to_string()));
+    }
+}
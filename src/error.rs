@@ -0,0 +1,209 @@
+use crate::schema::argument::ArgumentType;
+use crate::schema::path::PathConstraint;
+use crate::sensitive::REDACTED;
+use crate::token::split::SplitError;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    UnknownArgument {
+        arg: String,
+        suggestions: Vec<String>,
+    },
+    AmbiguousArgument {
+        arg: String,
+        candidates: Vec<String>,
+    },
+    UnexpectedValue(String),
+    MissingValue(String),
+    InvalidValue {
+        name: String,
+        arg_type: ArgumentType,
+        value: String,
+    },
+    InvalidUtf8 {
+        name: String,
+        value: String,
+    },
+    MissingRequired(String),
+    InvalidEnv {
+        var: String,
+        arg_type: ArgumentType,
+        value: String,
+    },
+    UnknownConfigKey {
+        key: String,
+        line: usize,
+    },
+    InvalidConfig {
+        key: String,
+        line: usize,
+        arg_type: ArgumentType,
+        value: String,
+    },
+    ResponseFile {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+    Validation {
+        name: String,
+        value: String,
+        message: String,
+    },
+    InvalidChoice {
+        name: String,
+        value: String,
+        choices: Vec<String>,
+        suggestions: Vec<String>,
+    },
+    Conflict(Vec<String>),
+    Duplicate(String),
+    MissingOneOf(Vec<String>),
+    Requires {
+        option: String,
+        required: String,
+    },
+    RequiredIf {
+        option: String,
+        other: String,
+        value: String,
+    },
+    Multiple(Vec<Error>),
+    Split(SplitError),
+    Path {
+        name: String,
+        path: PathBuf,
+        constraint: PathConstraint,
+    },
+    Input {
+        name: String,
+        source: String,
+        message: String,
+    },
+}
+
+impl Error {
+    /// Replaces the offending value with a placeholder, for errors about
+    /// sensitive entries.
+    pub fn redacted(self) -> Self {
+        match self {
+            Error::InvalidValue { name, arg_type, .. } => Error::InvalidValue {
+                name,
+                arg_type,
+                value: REDACTED.to_string(),
+            },
+            Error::InvalidUtf8 { name, .. } => Error::InvalidUtf8 {
+                name,
+                value: REDACTED.to_string(),
+            },
+            Error::InvalidEnv { var, arg_type, .. } => Error::InvalidEnv {
+                var,
+                arg_type,
+                value: REDACTED.to_string(),
+            },
+            Error::InvalidConfig { key, line, arg_type, .. } => Error::InvalidConfig {
+                key,
+                line,
+                arg_type,
+                value: REDACTED.to_string(),
+            },
+            Error::Validation { name, message, .. } => Error::Validation {
+                name,
+                value: REDACTED.to_string(),
+                message,
+            },
+            Error::InvalidChoice { name, choices, .. } => Error::InvalidChoice {
+                name,
+                value: REDACTED.to_string(),
+                choices,
+                suggestions: Vec::new(),
+            },
+            Error::Path { name, constraint, .. } => Error::Path {
+                name,
+                path: PathBuf::from(REDACTED),
+                constraint,
+            },
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownArgument { arg, suggestions } => {
+                write!(f, "Expects valid argument: {arg}{}", did_you_mean(suggestions))
+            },
+            Error::AmbiguousArgument { arg, candidates } => {
+                write!(f, "Ambiguous argument: {arg} could be {}", candidates.join(", "))
+            },
+            Error::UnexpectedValue(arg) => write!(f, "Unexpected value for flag: {arg}"),
+            Error::MissingValue(name) => write!(f, "Missing value for argument: {name}"),
+            Error::InvalidValue { name, arg_type, value } => {
+                write!(f, "Invalid {} for {name}: {value}", arg_type.name())
+            },
+            Error::InvalidUtf8 { name, value } => write!(f, "Invalid UTF-8 for {name}: {value}"),
+            Error::MissingRequired(name) => write!(f, "Missing required argument: {name}"),
+            Error::InvalidEnv { var, arg_type, value } => {
+                write!(f, "Invalid {} in environment variable {var}: {value}", arg_type.name())
+            },
+            Error::UnknownConfigKey { key, line } => {
+                write!(f, "Unknown key in config at line {line}: {key}")
+            },
+            Error::InvalidConfig { key, line, arg_type, value } => {
+                write!(f, "Invalid {} for {key} in config at line {line}: {value}", arg_type.name())
+            },
+            Error::ResponseFile { path, line: Some(line), message } => {
+                write!(f, "{}:{line}: {message}", path.display())
+            },
+            Error::ResponseFile { path, line: None, message } => {
+                write!(f, "{}: {message}", path.display())
+            },
+            Error::Validation { name, value, message } => {
+                write!(f, "Invalid value for {name}: {value}, {message}")
+            },
+            Error::InvalidChoice { name, value, choices, suggestions } => write!(
+                f,
+                "Invalid value for {name}: {value}, expected one of: {}{}",
+                choices.join(", "),
+                did_you_mean(suggestions),
+            ),
+            Error::Conflict(options) => write!(f, "Options cannot be used together: {}", options.join(", ")),
+            Error::Duplicate(option) => write!(f, "Option {option} given more than once"),
+            Error::MissingOneOf(options) => write!(f, "Expects one of: {}", options.join(", ")),
+            Error::Requires { option, required } => write!(f, "Option {option} requires {required}"),
+            Error::RequiredIf { option, other, value } => {
+                write!(f, "Option {option} is required when {other} is {value}")
+            },
+            Error::Multiple(errors) => {
+                let messages: Vec<String> = errors.iter().map(Error::to_string).collect();
+                write!(f, "{}", messages.join("\n"))
+            },
+            Error::Split(err) => write!(f, "{err}"),
+            Error::Path { name, path, constraint } => {
+                write!(f, "Path for {name} {constraint}: {}", path.display())
+            },
+            Error::Input { name, source, message } => {
+                write!(f, "Cannot read value for {name} from {source}: {message}")
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [suggestion] => format!(" (did you mean {suggestion}?)"),
+        suggestions => format!(" (did you mean one of {}?)", suggestions.join(", ")),
+    }
+}
+
+impl From<SplitError> for Error {
+    fn from(err: SplitError) -> Self {
+        Error::Split(err)
+    }
+}
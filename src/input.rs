@@ -0,0 +1,61 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+pub trait Input {
+    fn read_stdin(&self) -> io::Result<String>;
+    fn read_file(&self, path: &Path) -> io::Result<String>;
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct StdInput;
+
+impl Input for StdInput {
+    fn read_stdin(&self) -> io::Result<String> {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents)?;
+        Ok(contents)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+}
+
+/// Refuses every read, for command lines from sources that must not reach
+/// stdin or files.
+#[derive(Default, Clone, Copy)]
+pub struct NoInput;
+
+impl Input for NoInput {
+    fn read_stdin(&self) -> io::Result<String> {
+        Err(disabled())
+    }
+
+    fn read_file(&self, _: &Path) -> io::Result<String> {
+        Err(disabled())
+    }
+}
+
+fn disabled() -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, "Reading input is disabled")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_read_file_contents() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("secret.txt");
+        fs::write(&file, "s3cret\n").unwrap();
+
+        // when
+        let contents = StdInput.read_file(&file).unwrap();
+
+        // then
+        assert_eq!(contents, "s3cret\n");
+    }
+}
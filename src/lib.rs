@@ -1,5 +1,11 @@
-mod schema;
-mod token;
-mod collection;
+pub mod schema;
+pub mod token;
+pub mod env;
+pub mod error;
+pub mod input;
+pub mod sensitive;
+mod suggest;
+pub mod collection;
+pub mod completion;
+pub mod doc;
 pub mod app;
-
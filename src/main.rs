@@ -1,12 +1,22 @@
 #![warn(clippy::all)]
 
 use args::app::App;
+use args::env::ProcessEnv;
 use std::env;
+use std::process;
 
 fn main() {
-    App::new(get_args()).run();
-}
+    let args: Vec<String> = env::args_os().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    if let Some(completion) = App::complete(&args, &ProcessEnv) {
+        print!("{completion}");
+        return;
+    }
 
-fn get_args() -> Vec<String> {
-    env::args().collect()
+    match App::try_new(env::args_os()) {
+        Ok(app) => app.run(),
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(2);
+        },
+    }
 }
@@ -1,41 +1,216 @@
-use std::collections::HashMap;
 use crate::schema::argument::ArgumentType;
-use crate::schema::argument::ArgumentTypeFactory;
+use crate::schema::builder::SchemaBuilder;
+use crate::schema::duplicates::Duplicates;
+use crate::schema::entry::Entry;
+use crate::schema::error::SchemaError;
+use crate::schema::group::Group;
+use crate::schema::index::Index;
+use crate::schema::order::ArgumentOrder;
 
 pub mod argument;
+pub mod builder;
+pub mod duplicates;
+pub mod entry;
+pub mod error;
+#[cfg(any(feature = "toml", feature = "json"))]
+mod file;
+pub mod group;
+mod index;
+pub mod order;
+pub mod path;
+pub mod validator;
+pub mod value;
 
-#[derive(Default, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Schema {
-    map: HashMap<String, String>,
+    entries: Vec<Entry>,
+    index: Index,
+    env_prefix: Option<String>,
+    subcommands: Vec<(String, Schema)>,
+    abbreviations: bool,
+    collect_unknown: bool,
+    positionals: bool,
+    order: ArgumentOrder,
+    name: Option<String>,
+    description: Option<String>,
+    groups: Vec<Group>,
+    duplicates: Duplicates,
 }
 
 impl Schema {
     pub fn new() -> Self {
-        Self {
-            map: HashMap::new(),
-        }
+        Self::default()
+    }
+
+    pub fn builder() -> SchemaBuilder {
+        SchemaBuilder::new()
     }
 
     pub fn from(entries: Vec<(String, String)>) -> Self {
-        let map = entries
+        Self::try_from(entries).expect("Expects valid schema")
+    }
+
+    pub fn from_entries(entries: Vec<Entry>) -> Self {
+        entries
             .into_iter()
-            .collect();
+            .fold(Self::builder(), SchemaBuilder::entry)
+            .build()
+            .expect("Expects valid schema")
+    }
 
+    fn from_valid_entries(entries: Vec<Entry>) -> Self {
         Self {
-            map,
+            index: Index::new(&entries),
+            entries,
+            env_prefix: None,
+            subcommands: Vec::new(),
+            abbreviations: false,
+            collect_unknown: false,
+            positionals: false,
+            order: ArgumentOrder::default(),
+            name: None,
+            description: None,
+            groups: Vec::new(),
+            duplicates: Duplicates::default(),
         }
     }
 
-    pub fn get(&self, c: char) -> Option<ArgumentType> {
-        let key = c.to_string();
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 
-        self.map.get(&key)?.arg_type()
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// The policy for repeated occurrences of the entry: its own if set,
+    /// the schema-wide one otherwise.
+    pub fn duplicates(&self, entry: &Entry) -> Duplicates {
+        entry.duplicates().unwrap_or(self.duplicates)
+    }
+
+    /// How the entry is spelled on the command line when no spelling typed
+    /// by the user is known: its long form if it has one.
+    pub fn spelling(&self, name: &str) -> String {
+        match self.entry(name) {
+            Some(entry) => match (entry.long(), entry.short()) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => name.to_string(),
+            },
+            None => name.to_string(),
+        }
+    }
+
+    pub fn get(&self, c: char) -> Option<ArgumentType> {
+        self.index
+            .short(c)
+            .map(|position| self.entries[position].arg_type().clone())
     }
 
     pub fn str_to_argtype(&self, string: &str) -> Option<ArgumentType> {
-        let c = string.chars().nth(1).expect("Expects valid String");
+        self.resolve(string).map(|entry| entry.arg_type().clone())
+    }
+
+    pub fn resolve(&self, arg: &str) -> Option<&Entry> {
+        self.index.arg(arg).map(|position| &self.entries[position])
+    }
+
+    /// Resolves `arg` like `resolve`, additionally accepting a prefix of a
+    /// long option when abbreviations are enabled and the prefix matches a
+    /// single entry. Otherwise returns the ambiguous spellings.
+    pub fn resolve_abbreviated(&self, arg: &str) -> Result<Option<&Entry>, Vec<String>> {
+        if let Some(entry) = self.resolve(arg) {
+            return Ok(Some(entry));
+        }
+        let Some(prefix) = arg.strip_prefix("--").filter(|prefix| self.abbreviations && !prefix.is_empty()) else {
+            return Ok(None);
+        };
+
+        match self.index.long_prefix(prefix).as_slice() {
+            [] => Ok(None),
+            [(position, _)] => Ok(Some(&self.entries[*position])),
+            matches => Err(matches
+                .iter()
+                .flat_map(|(_, longs)| longs.iter().map(|long| format!("--{long}")))
+                .collect()),
+        }
+    }
+
+    pub fn allows_abbreviations(&self) -> bool {
+        self.abbreviations
+    }
+
+    /// Whether options not defined in the schema are collected for
+    /// passthrough rather than rejected.
+    pub fn collects_unknown(&self) -> bool {
+        self.collect_unknown
+    }
+
+    pub fn accepts_positionals(&self) -> bool {
+        self.positionals
+    }
+
+    pub fn order(&self) -> ArgumentOrder {
+        self.order
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&Entry> {
+        self.index.name(name).map(|position| &self.entries[position])
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn subcommand(&self, name: &str) -> Option<&Schema> {
+        self.subcommands
+            .iter()
+            .find(|(subcommand, _)| subcommand == name)
+            .map(|(_, schema)| schema)
+    }
+
+    pub fn subcommands(&self) -> impl Iterator<Item = (&str, &Schema)> {
+        self.subcommands.iter().map(|(name, schema)| (name.as_str(), schema))
+    }
+
+    // Every way an option of this schema can be written on the command
+    // line, followed by the names of its subcommands.
+    pub(crate) fn spellings(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .flat_map(builder::spellings_of)
+            .chain(self.subcommands.iter().map(|(name, _)| name.clone()))
+            .collect()
+    }
+
+    pub fn env_prefix(&self) -> Option<&str> {
+        self.env_prefix.as_deref()
+    }
 
-        self.get(c)
+    pub fn env_var(&self, entry: &Entry) -> Option<String> {
+        if let Some(env) = entry.env() {
+            return Some(env.to_string());
+        }
+        let prefix = self.env_prefix.as_ref()?;
+        let name = entry.name().to_uppercase().replace('-', "_");
+
+        Some(format!("{prefix}_{name}"))
+    }
+}
+
+impl TryFrom<Vec<(String, String)>> for Schema {
+    type Error = SchemaError;
+
+    fn try_from(entries: Vec<(String, String)>) -> Result<Self, Self::Error> {
+        entries
+            .iter()
+            .fold(Self::builder(), |builder, (name, arg_type)| builder.argument(name, arg_type))
+            .build()
     }
 }
 
@@ -114,4 +289,70 @@ mod tests {
         assert_eq!(schema.get('s'), Some(ArgumentType::Str));
         assert_eq!(schema.get('b'), Some(ArgumentType::Bool));
     }
+
+    #[test]
+    fn should_resolve_long_names_and_aliases() {
+        // given
+        let schema = Schema::from_entries(vec![
+            Entry::new("verbose", ArgumentType::Bool).with_short('v').with_alias("loud"),
+            Entry::new("count", ArgumentType::Int),
+        ]);
+
+        // then
+        assert_eq!(schema.str_to_argtype("-v"), Some(ArgumentType::Bool));
+        assert_eq!(schema.str_to_argtype("--verbose"), Some(ArgumentType::Bool));
+        assert_eq!(schema.str_to_argtype("--loud"), Some(ArgumentType::Bool));
+        assert_eq!(schema.str_to_argtype("--count"), Some(ArgumentType::Int));
+        assert_eq!(schema.str_to_argtype("-c"), None);
+        assert_eq!(schema.resolve("--count").map(Entry::name), Some("count"));
+    }
+
+    #[test]
+    fn should_fail_to_convert_invalid_entries() {
+        // given
+        let entries = vec![
+            ("s".to_string(), "string".to_string()),
+            ("s".to_string(), "int".to_string()),
+        ];
+
+        // when
+        let result = Schema::try_from(entries);
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::DuplicateName("s".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expects valid schema")]
+    fn should_panic_on_unknown_type() {
+        Schema::from(vec![
+            ("f".to_string(), "float".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn should_derive_env_var_from_prefix() {
+        // given
+        let schema = Schema::builder()
+            .env_prefix("MYTOOL")
+            .option::<String>('o', "output-file")
+            .option::<i64>('n', "count").env("COUNT")
+            .build()
+            .unwrap();
+
+        // then
+        assert_eq!(schema.env_var(schema.entry("output-file").unwrap()), Some("MYTOOL_OUTPUT_FILE".to_string()));
+        assert_eq!(schema.env_var(schema.entry("count").unwrap()), Some("COUNT".to_string()));
+    }
+
+    #[test]
+    fn should_not_have_env_var_by_default() {
+        // given
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+
+        // then
+        assert_eq!(schema.env_var(schema.entry("s").unwrap()), None);
+    }
 }
@@ -3,15 +3,36 @@ pub enum ArgumentType {
     Bool,
     Str,
     Int,
+    OsStr,
+    Path,
 }
 
 impl ArgumentType {
     fn from(string: &str) -> Option<Self> {
-        match string {
+        match Self::from_name(string) {
+            Some(arg_type) => Some(arg_type),
+            None => panic!("invalid argument!"),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
             "bool" => Some(ArgumentType::Bool),
             "string" => Some(ArgumentType::Str),
             "int" => Some(ArgumentType::Int),
-            &_ => panic!("invalid argument!"),
+            "os_string" => Some(ArgumentType::OsStr),
+            "path" => Some(ArgumentType::Path),
+            &_ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArgumentType::Bool => "bool",
+            ArgumentType::Str => "string",
+            ArgumentType::Int => "int",
+            ArgumentType::OsStr => "os_string",
+            ArgumentType::Path => "path",
         }
     }
 }
@@ -36,5 +57,16 @@ mod tests {
         assert_eq!(ArgumentType::from("string"), Some(ArgumentType::Str));
         assert_eq!(ArgumentType::from("int"), Some(ArgumentType::Int));
     }
-}
 
+    #[test]
+    fn should_return_none_for_unknown_type_name() {
+        assert_eq!(ArgumentType::from_name("float"), None);
+    }
+
+    #[test]
+    fn should_round_trip_type_name() {
+        for arg_type in [ArgumentType::Bool, ArgumentType::Str, ArgumentType::Int, ArgumentType::OsStr, ArgumentType::Path] {
+            assert_eq!(ArgumentType::from_name(arg_type.name()), Some(arg_type));
+        }
+    }
+}
@@ -0,0 +1,777 @@
+use crate::completion::dynamic::{Candidates, Context};
+use crate::schema::argument::ArgumentType;
+use crate::schema::duplicates::Duplicates;
+use crate::schema::entry::Entry;
+use crate::schema::error::SchemaError;
+use crate::schema::group::{Group, GroupKind};
+use crate::schema::order::ArgumentOrder;
+use crate::schema::path::PathConstraint;
+use crate::schema::validator::Validator;
+use crate::schema::value::{ArgumentValue, Value};
+use crate::schema::Schema;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+#[derive(Default)]
+pub struct SchemaBuilder {
+    entries: Vec<Result<Entry, SchemaError>>,
+    env_prefix: Option<String>,
+    subcommands: Vec<(String, Schema)>,
+    abbreviations: bool,
+    collect_unknown: bool,
+    positionals: bool,
+    order: ArgumentOrder,
+    name: Option<String>,
+    description: Option<String>,
+    groups: Vec<Group>,
+    duplicates: Duplicates,
+}
+
+impl SchemaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entry(mut self, entry: Entry) -> Self {
+        self.entries.push(Ok(entry));
+        self
+    }
+
+    pub fn argument(mut self, name: &str, type_name: &str) -> Self {
+        let entry = match ArgumentType::from_name(type_name) {
+            Some(arg_type) => Ok(Entry::new(name, arg_type)),
+            None => Err(SchemaError::UnknownType {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+            }),
+        };
+        self.entries.push(entry);
+        self
+    }
+
+    pub fn env_prefix(self, prefix: &str) -> Self {
+        Self {
+            env_prefix: Some(prefix.to_string()),
+            ..self
+        }
+    }
+
+    pub fn abbreviations(self, abbreviations: bool) -> Self {
+        Self {
+            abbreviations,
+            ..self
+        }
+    }
+
+    pub fn collect_unknown(self, collect_unknown: bool) -> Self {
+        Self {
+            collect_unknown,
+            ..self
+        }
+    }
+
+    pub fn positionals(self, positionals: bool) -> Self {
+        Self {
+            positionals,
+            ..self
+        }
+    }
+
+    pub fn order(self, order: ArgumentOrder) -> Self {
+        Self {
+            order,
+            ..self
+        }
+    }
+
+    pub fn duplicates(self, duplicates: Duplicates) -> Self {
+        Self {
+            duplicates,
+            ..self
+        }
+    }
+
+    pub fn group(mut self, kind: GroupKind, members: &[&str]) -> Self {
+        self.groups.push(Group::new(kind, members));
+        self
+    }
+
+    pub fn name(self, name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            ..self
+        }
+    }
+
+    pub fn description(self, description: &str) -> Self {
+        Self {
+            description: Some(description.to_string()),
+            ..self
+        }
+    }
+
+    pub fn subcommand(mut self, name: &str, schema: Schema) -> Self {
+        self.subcommands.push((name.to_string(), schema));
+        self
+    }
+
+    pub fn flag(self, short: char, long: &str) -> EntryBuilder<bool> {
+        self.option(short, long)
+    }
+
+    pub fn option<T: ArgumentValue>(self, short: char, long: &str) -> EntryBuilder<T> {
+        EntryBuilder {
+            builder: self,
+            entry: Entry::new(long, T::arg_type()).with_short(short),
+            value_type: PhantomData,
+        }
+    }
+
+    pub fn build(self) -> Result<Schema, SchemaError> {
+        self.build_indexed().map_err(|(_, err)| err)
+    }
+
+    // Reports the position of the offending entry alongside the error, so
+    // that schema files can point at the definition that caused it.
+    pub(crate) fn build_indexed(self) -> Result<Schema, (usize, SchemaError)> {
+        let mut names: HashMap<String, usize> = HashMap::new();
+        let mut spellings: HashMap<String, String> = HashMap::new();
+        let mut entries = Vec::new();
+
+        for (index, entry) in self.entries.into_iter().enumerate() {
+            let entry = entry.map_err(|err| (index, err))?;
+
+            if entry.name().is_empty() {
+                return Err((index, SchemaError::EmptyName));
+            }
+            if names.insert(entry.name().to_string(), index).is_some() {
+                return Err((index, SchemaError::DuplicateName(entry.name().to_string())));
+            }
+
+            if !entry.path_constraints().is_empty() && *entry.arg_type() != ArgumentType::Path {
+                return Err((index, SchemaError::InvalidConstraint {
+                    name: entry.name().to_string(),
+                    message: "path constraints require a path argument".to_string(),
+                }));
+            }
+
+            if entry.default().is_some_and(|default| default.arg_type() != *entry.arg_type()) {
+                return Err((index, SchemaError::InvalidConstraint {
+                    name: entry.name().to_string(),
+                    message: format!("default must be of type `{}`", entry.arg_type().name()),
+                }));
+            }
+
+            if entry.reads_input() && *entry.arg_type() != ArgumentType::Str {
+                return Err((index, SchemaError::InvalidConstraint {
+                    name: entry.name().to_string(),
+                    message: "reading input requires a string argument".to_string(),
+                }));
+            }
+
+            if entry.validators().iter().any(|validator| !validator.applies_to(entry.arg_type())) {
+                return Err((index, SchemaError::InvalidConstraint {
+                    name: entry.name().to_string(),
+                    message: format!("validator does not apply to a {} argument", entry.arg_type().name()),
+                }));
+            }
+
+            if !entry.choices().is_empty() {
+                if *entry.arg_type() != ArgumentType::Str {
+                    return Err((index, SchemaError::InvalidConstraint {
+                        name: entry.name().to_string(),
+                        message: "choices require a string argument".to_string(),
+                    }));
+                }
+                if let Some(Value::Str(default)) = entry.default() {
+                    if !entry.choices().contains(default) {
+                        return Err((index, SchemaError::InvalidConstraint {
+                            name: entry.name().to_string(),
+                            message: format!("default `{default}` is not one of the choices"),
+                        }));
+                    }
+                }
+            }
+
+            let entry_spellings = spellings_of(&entry);
+            if entry_spellings.is_empty() || entry_spellings.iter().any(|spelling| !is_reachable(spelling)) {
+                return Err((index, SchemaError::Unreachable(entry.name().to_string())));
+            }
+            for spelling in entry_spellings {
+                if let Some(first) = spellings.get(&spelling) {
+                    if first != entry.name() {
+                        return Err((index, SchemaError::Collision {
+                            spelling,
+                            first: first.clone(),
+                            second: entry.name().to_string(),
+                        }));
+                    }
+                }
+                spellings.insert(spelling, entry.name().to_string());
+            }
+
+            entries.push(entry);
+        }
+
+        let index = entries.len();
+        for (position, (name, _)) in self.subcommands.iter().enumerate() {
+            if name.is_empty() {
+                return Err((index, SchemaError::EmptyName));
+            }
+            if name.starts_with('-') || name.contains(char::is_whitespace) {
+                return Err((index, SchemaError::Unreachable(name.clone())));
+            }
+            if self.subcommands[..position].iter().any(|(other, _)| other == name) {
+                return Err((index, SchemaError::DuplicateSubcommand(name.clone())));
+            }
+        }
+
+        for (position, entry) in entries.iter().enumerate() {
+            if let Some(other) = entry.related().find(|other| !names.contains_key(*other)) {
+                return Err((position, SchemaError::InvalidConstraint {
+                    name: entry.name().to_string(),
+                    message: format!("refers to undefined argument `{other}`"),
+                }));
+            }
+            for (other, value) in entry.required_if() {
+                let other_type = entries[names[other]].arg_type();
+                if value.arg_type() != *other_type {
+                    return Err((position, SchemaError::InvalidConstraint {
+                        name: entry.name().to_string(),
+                        message: format!("value for `{other}` must be of type `{}`", other_type.name()),
+                    }));
+                }
+            }
+        }
+        for group in &self.groups {
+            if let Some(member) = group.members().iter().find(|member| !names.contains_key(*member)) {
+                return Err((index, SchemaError::InvalidConstraint {
+                    name: member.clone(),
+                    message: "group member is not defined".to_string(),
+                }));
+            }
+        }
+
+        let mut schema = Schema::from_valid_entries(entries);
+        schema.env_prefix = self.env_prefix;
+        schema.subcommands = self.subcommands;
+        schema.abbreviations = self.abbreviations;
+        schema.collect_unknown = self.collect_unknown;
+        schema.positionals = self.positionals;
+        schema.order = self.order;
+        schema.name = self.name;
+        schema.description = self.description;
+        schema.groups = self.groups;
+        schema.duplicates = self.duplicates;
+
+        Ok(schema)
+    }
+}
+
+pub struct EntryBuilder<T: ArgumentValue> {
+    builder: SchemaBuilder,
+    entry: Entry,
+    value_type: PhantomData<T>,
+}
+
+impl<T: ArgumentValue> EntryBuilder<T> {
+    pub fn help(self, help: &str) -> Self {
+        Self {
+            entry: self.entry.with_help(help),
+            ..self
+        }
+    }
+
+    pub fn alias(self, alias: &str) -> Self {
+        Self {
+            entry: self.entry.with_alias(alias),
+            ..self
+        }
+    }
+
+    pub fn default(self, default: impl Into<T>) -> Self {
+        Self {
+            entry: self.entry.with_default(default.into().into()),
+            ..self
+        }
+    }
+
+    pub fn required(self) -> Self {
+        Self {
+            entry: self.entry.with_required(true),
+            ..self
+        }
+    }
+
+    pub fn env(self, env: &str) -> Self {
+        Self {
+            entry: self.entry.with_env(env),
+            ..self
+        }
+    }
+
+    pub fn sensitive(self) -> Self {
+        Self {
+            entry: self.entry.with_sensitive(true),
+            ..self
+        }
+    }
+
+    pub fn requires(self, other: &str) -> Self {
+        Self {
+            entry: self.entry.with_requires(other),
+            ..self
+        }
+    }
+
+    pub fn conflicts_with(self, other: &str) -> Self {
+        Self {
+            entry: self.entry.with_conflict(other),
+            ..self
+        }
+    }
+
+    pub fn required_if(self, other: &str, value: impl Into<Value>) -> Self {
+        Self {
+            entry: self.entry.with_required_if(other, value.into()),
+            ..self
+        }
+    }
+
+    pub fn validate(self, check: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        let validator = Validator::custom(move |value| {
+            check(T::from_value(value).expect("Values match the type of their entry"))
+        });
+        Self {
+            entry: self.entry.with_validator(validator),
+            ..self
+        }
+    }
+
+    pub fn duplicates(self, duplicates: Duplicates) -> Self {
+        Self {
+            entry: self.entry.with_duplicates(duplicates),
+            ..self
+        }
+    }
+
+    pub fn candidates(self, candidates: impl Fn(&Context) -> Vec<String> + Send + Sync + 'static) -> Self {
+        Self {
+            entry: self.entry.with_candidates(Candidates::new(candidates)),
+            ..self
+        }
+    }
+
+    pub fn flag(self, short: char, long: &str) -> EntryBuilder<bool> {
+        self.done().flag(short, long)
+    }
+
+    pub fn option<U: ArgumentValue>(self, short: char, long: &str) -> EntryBuilder<U> {
+        self.done().option(short, long)
+    }
+
+    pub fn entry(self, entry: Entry) -> SchemaBuilder {
+        self.done().entry(entry)
+    }
+
+    pub fn argument(self, name: &str, type_name: &str) -> SchemaBuilder {
+        self.done().argument(name, type_name)
+    }
+
+    pub fn subcommand(self, name: &str, schema: Schema) -> SchemaBuilder {
+        self.done().subcommand(name, schema)
+    }
+
+    pub fn abbreviations(self, abbreviations: bool) -> SchemaBuilder {
+        self.done().abbreviations(abbreviations)
+    }
+
+    pub fn collect_unknown(self, collect_unknown: bool) -> SchemaBuilder {
+        self.done().collect_unknown(collect_unknown)
+    }
+
+    pub fn positionals(self, positionals: bool) -> SchemaBuilder {
+        self.done().positionals(positionals)
+    }
+
+    pub fn order(self, order: ArgumentOrder) -> SchemaBuilder {
+        self.done().order(order)
+    }
+
+    pub fn group(self, kind: GroupKind, members: &[&str]) -> SchemaBuilder {
+        self.done().group(kind, members)
+    }
+
+    pub fn build(self) -> Result<Schema, SchemaError> {
+        self.done().build()
+    }
+
+    pub fn done(self) -> SchemaBuilder {
+        self.builder.entry(self.entry)
+    }
+}
+
+impl EntryBuilder<i64> {
+    pub fn min(self, min: i64) -> Self {
+        Self {
+            entry: self.entry.with_validator(Validator::Min(min)),
+            ..self
+        }
+    }
+
+    pub fn max(self, max: i64) -> Self {
+        Self {
+            entry: self.entry.with_validator(Validator::Max(max)),
+            ..self
+        }
+    }
+}
+
+impl EntryBuilder<String> {
+    pub fn min_length(self, min: usize) -> Self {
+        Self {
+            entry: self.entry.with_validator(Validator::MinLength(min)),
+            ..self
+        }
+    }
+
+    pub fn max_length(self, max: usize) -> Self {
+        Self {
+            entry: self.entry.with_validator(Validator::MaxLength(max)),
+            ..self
+        }
+    }
+
+    pub fn non_empty(self) -> Self {
+        Self {
+            entry: self.entry.with_validator(Validator::NonEmpty),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn matches(self, regex: regex::Regex) -> Self {
+        Self {
+            entry: self.entry.with_validator(Validator::Regex(regex)),
+            ..self
+        }
+    }
+
+    pub fn choices(self, choices: &[&str]) -> Self {
+        Self {
+            entry: choices.iter().fold(self.entry, |entry, choice| entry.with_choice(choice)),
+            ..self
+        }
+    }
+
+    pub fn read_input(self) -> Self {
+        Self {
+            entry: self.entry.with_reads_input(true),
+            ..self
+        }
+    }
+}
+
+impl EntryBuilder<PathBuf> {
+    pub fn constraint(self, constraint: PathConstraint) -> Self {
+        Self {
+            entry: self.entry.with_path_constraint(constraint),
+            ..self
+        }
+    }
+}
+
+pub(crate) fn spellings_of(entry: &Entry) -> Vec<String> {
+    let mut spellings = Vec::new();
+
+    if let Some(short) = entry.short() {
+        spellings.push(format!("-{short}"));
+    }
+    if let Some(long) = entry.long() {
+        spellings.push(format!("--{long}"));
+    }
+    for alias in entry.aliases() {
+        if alias.chars().count() == 1 {
+            spellings.push(format!("-{alias}"));
+        } else {
+            spellings.push(format!("--{alias}"));
+        }
+    }
+
+    spellings
+}
+
+fn is_reachable(spelling: &str) -> bool {
+    let name = spelling.trim_start_matches('-');
+    let dashes = spelling.len() - name.len();
+
+    !name.is_empty()
+        && (dashes == 1 || dashes == 2)
+        && !name.contains(|c: char| c.is_whitespace() || c == '=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::value::Value;
+
+    #[test]
+    fn should_build_valid_schema() {
+        // given
+        let builder = SchemaBuilder::new()
+            .argument("b", "bool")
+            .entry(Entry::new("verbose", ArgumentType::Bool).with_short('v'));
+
+        // when
+        let schema = builder.build().unwrap();
+
+        // then
+        assert_eq!(schema.get('b'), Some(ArgumentType::Bool));
+        assert_eq!(schema.str_to_argtype("--verbose"), Some(ArgumentType::Bool));
+    }
+
+    #[test]
+    fn should_reject_unknown_type() {
+        // given
+        let builder = SchemaBuilder::new()
+            .argument("f", "float");
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::UnknownType {
+            name: "f".to_string(),
+            type_name: "float".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_reject_duplicate_names() {
+        // given
+        let builder = SchemaBuilder::new()
+            .argument("s", "string")
+            .argument("s", "int");
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::DuplicateName("s".to_string()));
+    }
+
+    #[test]
+    fn should_reject_empty_name() {
+        // given
+        let builder = SchemaBuilder::new()
+            .argument("", "string");
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::EmptyName);
+    }
+
+    #[test]
+    fn should_reject_unreachable_names() {
+        for name in ["-x", "a b", "key=value"] {
+            // given
+            let builder = SchemaBuilder::new()
+                .argument(name, "string");
+
+            // when
+            let result = builder.build();
+
+            // then
+            assert_eq!(result.unwrap_err(), SchemaError::Unreachable(name.to_string()));
+        }
+    }
+
+    #[test]
+    fn should_reject_alias_collisions() {
+        // given
+        let builder = SchemaBuilder::new()
+            .entry(Entry::new("verbose", ArgumentType::Bool).with_short('v'))
+            .entry(Entry::new("version", ArgumentType::Bool).with_alias("v"));
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::Collision {
+            spelling: "-v".to_string(),
+            first: "verbose".to_string(),
+            second: "version".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_report_index_of_invalid_entry() {
+        // given
+        let builder = SchemaBuilder::new()
+            .argument("a", "int")
+            .argument("b", "int")
+            .argument("a", "bool");
+
+        // when
+        let result = builder.build_indexed();
+
+        // then
+        assert_eq!(result.unwrap_err().0, 2);
+    }
+
+    #[test]
+    fn should_build_schema_fluently() {
+        // given
+        let builder = Schema::builder()
+            .flag('v', "verbose").help("Print more output")
+            .option::<i64>('n', "count").default(1).required()
+            .option::<String>('o', "output").alias("out").default("out.txt");
+
+        // when
+        let schema = builder.build().unwrap();
+
+        // then
+        let verbose = schema.entry("verbose").unwrap();
+        assert_eq!(verbose.help(), Some("Print more output"));
+        assert_eq!(schema.str_to_argtype("-v"), Some(ArgumentType::Bool));
+        let count = schema.entry("count").unwrap();
+        assert_eq!(count.arg_type(), &ArgumentType::Int);
+        assert_eq!(count.default(), Some(&Value::Int(1)));
+        assert!(count.is_required());
+        assert_eq!(schema.str_to_argtype("--out"), Some(ArgumentType::Str));
+        assert_eq!(schema.entry("output").unwrap().default(), Some(&Value::Str("out.txt".to_string())));
+    }
+
+    #[test]
+    fn should_validate_fluent_entries() {
+        // given
+        let builder = Schema::builder()
+            .flag('v', "verbose")
+            .flag('v', "version");
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::Collision {
+            spelling: "-v".to_string(),
+            first: "verbose".to_string(),
+            second: "version".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_reject_path_constraints_on_other_types() {
+        // given
+        let builder = SchemaBuilder::new()
+            .entry(Entry::new("count", ArgumentType::Int).with_path_constraint(PathConstraint::Exists));
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.unwrap_err(), SchemaError::InvalidConstraint {
+            name: "count".to_string(),
+            message: "path constraints require a path argument".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_reject_default_of_other_type() {
+        // given
+        let builder = SchemaBuilder::new()
+            .entry(Entry::new("count", ArgumentType::Int).with_default(Value::Str("one".to_string())));
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.err(), Some(SchemaError::InvalidConstraint {
+            name: "count".to_string(),
+            message: "default must be of type `int`".to_string(),
+        }));
+    }
+
+    #[test]
+    fn should_reject_default_outside_choices() {
+        // given
+        let builder = SchemaBuilder::new()
+            .option::<String>('c', "color").choices(&["auto", "never"]).default("always");
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.err(), Some(SchemaError::InvalidConstraint {
+            name: "color".to_string(),
+            message: "default `always` is not one of the choices".to_string(),
+        }));
+    }
+
+    #[test]
+    fn should_reject_duplicate_subcommands() {
+        // given
+        let builder = SchemaBuilder::new()
+            .subcommand("add", Schema::new())
+            .subcommand("add", Schema::new());
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.err(), Some(SchemaError::DuplicateSubcommand("add".to_string())));
+    }
+
+    #[test]
+    fn should_reject_groups_with_undefined_members() {
+        // given
+        let builder = SchemaBuilder::new()
+            .flag('j', "json")
+            .group(GroupKind::AtMostOne, &["json", "yaml"]);
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.err(), Some(SchemaError::InvalidConstraint {
+            name: "yaml".to_string(),
+            message: "group member is not defined".to_string(),
+        }));
+    }
+
+    #[test]
+    fn should_reject_required_if_value_of_other_type() {
+        // given
+        let builder = SchemaBuilder::new()
+            .option::<i64>('p', "port")
+            .option::<String>('c', "cert").required_if("port", "443");
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.err(), Some(SchemaError::InvalidConstraint {
+            name: "cert".to_string(),
+            message: "value for `port` must be of type `int`".to_string(),
+        }));
+    }
+
+    #[test]
+    fn should_reject_validators_of_other_types() {
+        // given
+        let builder = SchemaBuilder::new()
+            .entry(Entry::new("name", ArgumentType::Str).with_validator(Validator::Min(1)));
+
+        // when
+        let result = builder.build();
+
+        // then
+        assert_eq!(result.err(), Some(SchemaError::InvalidConstraint {
+            name: "name".to_string(),
+            message: "validator does not apply to a string argument".to_string(),
+        }));
+    }
+}
@@ -0,0 +1,9 @@
+/// What happens when a single-valued option is given more than once on the
+/// command line.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Duplicates {
+    #[default]
+    LastWins,
+    FirstWins,
+    Error,
+}
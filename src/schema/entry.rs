@@ -0,0 +1,333 @@
+use crate::completion::dynamic::Candidates;
+use crate::schema::argument::ArgumentType;
+use crate::schema::duplicates::Duplicates;
+use crate::schema::path::PathConstraint;
+use crate::schema::validator::Validator;
+use crate::schema::value::Value;
+use crate::sensitive::Sensitive;
+use std::fmt;
+
+#[derive(PartialEq, Clone)]
+pub struct Entry {
+    name: String,
+    short: Option<char>,
+    long: Option<String>,
+    aliases: Vec<String>,
+    arg_type: ArgumentType,
+    default: Option<Value>,
+    required: bool,
+    help: Option<String>,
+    env: Option<String>,
+    path_constraints: Vec<PathConstraint>,
+    reads_input: bool,
+    sensitive: bool,
+    choices: Vec<String>,
+    candidates: Option<Candidates>,
+    requires: Vec<String>,
+    conflicts: Vec<String>,
+    required_if: Vec<(String, Value)>,
+    validators: Vec<Validator>,
+    duplicates: Option<Duplicates>,
+}
+
+impl Entry {
+    /// Creates an entry reachable through its name: `-n` for a single
+    /// character name, `--name` otherwise.
+    pub fn new(name: &str, arg_type: ArgumentType) -> Self {
+        let (short, long) = split_name(name);
+
+        Self {
+            name: name.to_string(),
+            short,
+            long,
+            aliases: Vec::new(),
+            arg_type,
+            default: None,
+            required: false,
+            help: None,
+            env: None,
+            path_constraints: Vec::new(),
+            reads_input: false,
+            sensitive: false,
+            choices: Vec::new(),
+            candidates: None,
+            requires: Vec::new(),
+            conflicts: Vec::new(),
+            required_if: Vec::new(),
+            validators: Vec::new(),
+            duplicates: None,
+        }
+    }
+
+    pub fn with_short(self, short: char) -> Self {
+        Self {
+            short: Some(short),
+            ..self
+        }
+    }
+
+    pub fn with_long(self, long: &str) -> Self {
+        Self {
+            long: Some(long.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    pub fn with_default(self, default: Value) -> Self {
+        Self {
+            default: Some(default),
+            ..self
+        }
+    }
+
+    pub fn with_required(self, required: bool) -> Self {
+        Self {
+            required,
+            ..self
+        }
+    }
+
+    pub fn with_help(self, help: &str) -> Self {
+        Self {
+            help: Some(help.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_env(self, env: &str) -> Self {
+        Self {
+            env: Some(env.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_path_constraint(mut self, constraint: PathConstraint) -> Self {
+        self.path_constraints.push(constraint);
+        self
+    }
+
+    pub fn with_reads_input(self, reads_input: bool) -> Self {
+        Self {
+            reads_input,
+            ..self
+        }
+    }
+
+    pub fn with_sensitive(self, sensitive: bool) -> Self {
+        Self {
+            sensitive,
+            ..self
+        }
+    }
+
+    pub fn with_choice(mut self, choice: &str) -> Self {
+        self.choices.push(choice.to_string());
+        self
+    }
+
+    pub fn with_candidates(self, candidates: Candidates) -> Self {
+        Self {
+            candidates: Some(candidates),
+            ..self
+        }
+    }
+
+    pub fn with_requires(mut self, other: &str) -> Self {
+        self.requires.push(other.to_string());
+        self
+    }
+
+    pub fn with_conflict(mut self, other: &str) -> Self {
+        self.conflicts.push(other.to_string());
+        self
+    }
+
+    pub fn with_required_if(mut self, other: &str, value: Value) -> Self {
+        self.required_if.push((other.to_string(), value));
+        self
+    }
+
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    pub fn with_duplicates(self, duplicates: Duplicates) -> Self {
+        Self {
+            duplicates: Some(duplicates),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn short(&self) -> Option<char> {
+        self.short
+    }
+
+    pub fn long(&self) -> Option<&str> {
+        self.long.as_deref()
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    pub fn arg_type(&self) -> &ArgumentType {
+        &self.arg_type
+    }
+
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    pub fn env(&self) -> Option<&str> {
+        self.env.as_deref()
+    }
+
+    pub fn path_constraints(&self) -> &[PathConstraint] {
+        &self.path_constraints
+    }
+
+    // Whether a value of `-` reads standard input and `@path` reads the
+    // contents of a file instead of being taken literally.
+    pub fn reads_input(&self) -> bool {
+        self.reads_input
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive
+    }
+
+    pub fn choices(&self) -> &[String] {
+        &self.choices
+    }
+
+    pub fn candidates(&self) -> Option<&Candidates> {
+        self.candidates.as_ref()
+    }
+
+    pub fn requires(&self) -> &[String] {
+        &self.requires
+    }
+
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+
+    pub fn required_if(&self) -> &[(String, Value)] {
+        &self.required_if
+    }
+
+    pub fn validators(&self) -> &[Validator] {
+        &self.validators
+    }
+
+    pub fn duplicates(&self) -> Option<Duplicates> {
+        self.duplicates
+    }
+
+    // Every other entry this one refers to.
+    pub(crate) fn related(&self) -> impl Iterator<Item = &str> {
+        self.requires
+            .iter()
+            .chain(&self.conflicts)
+            .chain(self.required_if.iter().map(|(other, _)| other))
+            .map(String::as_str)
+    }
+}
+
+impl fmt::Debug for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Entry");
+        debug
+            .field("name", &self.name)
+            .field("short", &self.short)
+            .field("long", &self.long)
+            .field("aliases", &self.aliases)
+            .field("arg_type", &self.arg_type);
+        match &self.default {
+            Some(default) if self.sensitive => debug.field("default", &Some(Sensitive::new(default))),
+            default => debug.field("default", default),
+        };
+        debug
+            .field("required", &self.required)
+            .field("help", &self.help)
+            .field("env", &self.env)
+            .field("path_constraints", &self.path_constraints)
+            .field("reads_input", &self.reads_input)
+            .field("sensitive", &self.sensitive)
+            .field("choices", &self.choices)
+            .field("candidates", &self.candidates)
+            .field("requires", &self.requires)
+            .field("conflicts", &self.conflicts)
+            .field("required_if", &self.required_if)
+            .field("validators", &self.validators)
+            .field("duplicates", &self.duplicates)
+            .finish()
+    }
+}
+
+fn split_name(name: &str) -> (Option<char>, Option<String>) {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => (Some(c), None),
+        (Some(_), Some(_)) => (None, Some(name.to_string())),
+        _ => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_use_single_char_name_as_short() {
+        // given
+        let entry = Entry::new("v", ArgumentType::Bool);
+
+        // then
+        assert_eq!(entry.short(), Some('v'));
+        assert_eq!(entry.long(), None);
+    }
+
+    #[test]
+    fn should_use_multi_char_name_as_long() {
+        // given
+        let entry = Entry::new("verbose", ArgumentType::Bool);
+
+        // then
+        assert_eq!(entry.short(), None);
+        assert_eq!(entry.long(), Some("verbose"));
+    }
+
+    #[test]
+    fn should_redact_default_of_sensitive_entry() {
+        // given
+        let entry = Entry::new("token", ArgumentType::Str)
+            .with_default(Value::Str("hunter2".to_string()))
+            .with_sensitive(true);
+
+        // when
+        let debug = format!("{entry:?}");
+
+        // then
+        assert!(debug.contains("default: Some(***)"), "{debug}");
+        assert!(!debug.contains("hunter2"), "{debug}");
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SchemaError {
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    EmptyName,
+    DuplicateName(String),
+    DuplicateSubcommand(String),
+    UnknownType {
+        name: String,
+        type_name: String,
+    },
+    Unreachable(String),
+    Collision {
+        spelling: String,
+        first: String,
+        second: String,
+    },
+    InvalidConstraint {
+        name: String,
+        message: String,
+    },
+}
+
+impl SchemaError {
+    pub fn at_offset(source: &str, offset: usize, message: String) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .map_or(0, |line| line.chars().count()) + 1;
+
+        SchemaError::Parse {
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Parse { line, column, message } => {
+                write!(f, "{message} at line {line}, column {column}")
+            },
+            SchemaError::EmptyName => write!(f, "argument name must not be empty"),
+            SchemaError::DuplicateName(name) => write!(f, "argument `{name}` is defined more than once"),
+            SchemaError::DuplicateSubcommand(name) => write!(f, "subcommand `{name}` is defined more than once"),
+            SchemaError::UnknownType { name, type_name } => {
+                write!(f, "unknown type `{type_name}` for argument `{name}`")
+            },
+            SchemaError::Unreachable(name) => {
+                write!(f, "argument `{name}` cannot be reached from the command line")
+            },
+            SchemaError::Collision { spelling, first, second } => {
+                write!(f, "`{spelling}` refers to both `{first}` and `{second}`")
+            },
+            SchemaError::InvalidConstraint { name, message } => {
+                write!(f, "invalid constraint on argument `{name}`: {message}")
+            },
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_compute_line_and_column_from_offset() {
+        // given
+        let source = "first\nsecond\nthird";
+
+        // when
+        let error = SchemaError::at_offset(source, 9, "oops".to_string());
+
+        // then
+        assert_eq!(error, SchemaError::Parse { line: 2, column: 4, message: "oops".to_string() });
+        assert_eq!(error.to_string(), "oops at line 2, column 4");
+    }
+}
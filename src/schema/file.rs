@@ -0,0 +1,412 @@
+use crate::schema::argument::ArgumentType;
+use crate::schema::builder::SchemaBuilder;
+use crate::schema::entry::Entry;
+use crate::schema::error::SchemaError;
+use crate::schema::path::PathConstraint;
+use crate::schema::value::Value;
+use crate::schema::Schema;
+use serde::{de, Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile<E> {
+    name: Option<String>,
+    description: Option<String>,
+    env_prefix: Option<String>,
+    #[serde(default)]
+    abbreviations: bool,
+    #[serde(default = "Vec::new")]
+    options: Vec<E>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEntry {
+    name: String,
+    short: Option<char>,
+    long: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(rename = "type")]
+    arg_type: ArgumentType,
+    default: Option<RawValue>,
+    #[serde(default)]
+    required: bool,
+    help: Option<String>,
+    env: Option<String>,
+    #[serde(default)]
+    path_constraints: Vec<PathConstraint>,
+    #[serde(default)]
+    read_input: bool,
+    #[serde(default)]
+    sensitive: bool,
+    #[serde(default)]
+    choices: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl<'de> Deserialize<'de> for ArgumentType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        ArgumentType::from_name(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown type `{name}`, expected `bool`, `string`, `int`, `os_string` or `path`")))
+    }
+}
+
+impl From<RawValue> for Value {
+    fn from(raw: RawValue) -> Self {
+        match raw {
+            RawValue::Bool(val) => Value::Bool(val),
+            RawValue::Int(val) => Value::Int(val),
+            RawValue::Str(val) => Value::Str(val),
+        }
+    }
+}
+
+impl TryFrom<RawEntry> for Entry {
+    type Error = String;
+
+    fn try_from(raw: RawEntry) -> Result<Self, Self::Error> {
+        let mut entry = Entry::new(&raw.name, raw.arg_type.clone())
+            .with_required(raw.required)
+            .with_reads_input(raw.read_input)
+            .with_sensitive(raw.sensitive);
+
+        if let Some(short) = raw.short {
+            entry = entry.with_short(short);
+        }
+        if let Some(long) = raw.long {
+            entry = entry.with_long(&long);
+        }
+        for alias in raw.aliases {
+            entry = entry.with_alias(&alias);
+        }
+        if let Some(help) = raw.help {
+            entry = entry.with_help(&help);
+        }
+        if let Some(env) = raw.env {
+            entry = entry.with_env(&env);
+        }
+        for choice in raw.choices {
+            entry = entry.with_choice(&choice);
+        }
+        for constraint in raw.path_constraints {
+            entry = entry.with_path_constraint(constraint);
+        }
+        if let Some(default) = raw.default {
+            let default = match (Value::from(default), &raw.arg_type) {
+                (Value::Str(val), ArgumentType::OsStr) => Value::OsStr(val.into()),
+                (Value::Str(val), ArgumentType::Path) => Value::Path(val.into()),
+                (default, _) => default,
+            };
+            if default.arg_type() != raw.arg_type {
+                return Err(format!(
+                    "default for `{}` must be of type `{}`",
+                    raw.name,
+                    raw.arg_type.name(),
+                ));
+            }
+            entry = entry.with_default(default);
+        }
+
+        Ok(entry)
+    }
+}
+
+impl Schema {
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, SchemaError> {
+        let mut file: SchemaFile<toml::Spanned<RawEntry>> = toml::from_str(source).map_err(|err| {
+            let offset = err.span().map_or(0, |span| span.start);
+            SchemaError::at_offset(source, offset, err.message().to_string())
+        })?;
+
+        let entries = std::mem::take(&mut file.options)
+            .into_iter()
+            .map(|spanned| (spanned.span().start, spanned.into_inner()))
+            .collect();
+
+        schema_from_raw(source, header(&file), entries)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json_str(source: &str) -> Result<Self, SchemaError> {
+        let mut file: SchemaFile<&serde_json::value::RawValue> = serde_json::from_str(source)
+            .map_err(|err| json_error(source, 0, err))?;
+
+        let entries = std::mem::take(&mut file.options)
+            .into_iter()
+            .map(|raw| {
+                let offset = raw.get().as_ptr() as usize - source.as_ptr() as usize;
+                serde_json::from_str(raw.get())
+                    .map(|entry| (offset, entry))
+                    .map_err(|err| json_error(source, offset, err))
+            })
+            .collect::<Result<_, _>>()?;
+
+        schema_from_raw(source, header(&file), entries)
+    }
+}
+
+// The schema-wide settings of a file, applied before its entries.
+fn header<E>(file: &SchemaFile<E>) -> SchemaBuilder {
+    let mut builder = Schema::builder().abbreviations(file.abbreviations);
+
+    if let Some(name) = &file.name {
+        builder = builder.name(name);
+    }
+    if let Some(description) = &file.description {
+        builder = builder.description(description);
+    }
+    if let Some(prefix) = &file.env_prefix {
+        builder = builder.env_prefix(prefix);
+    }
+    builder
+}
+
+fn schema_from_raw(
+    source: &str,
+    mut builder: SchemaBuilder,
+    entries: Vec<(usize, RawEntry)>,
+) -> Result<Schema, SchemaError> {
+    let mut offsets = Vec::new();
+
+    for (offset, raw) in entries {
+        let entry = Entry::try_from(raw)
+            .map_err(|message| SchemaError::at_offset(source, offset, message))?;
+        offsets.push(offset);
+        builder = builder.entry(entry);
+    }
+
+    builder
+        .build_indexed()
+        .map_err(|(index, err)| SchemaError::at_offset(source, offsets[index], err.to_string()))
+}
+
+// Errors of nested documents are relative to the nested slice, so they are
+// shifted by the slice offset to point into the whole source.
+#[cfg(feature = "json")]
+fn json_error(source: &str, offset: usize, err: serde_json::Error) -> SchemaError {
+    let message = err.to_string();
+    let message = match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message,
+    };
+    let nested = &source[offset..];
+    let line_start: usize = nested
+        .split_inclusive('\n')
+        .take(err.line().saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let column: usize = nested[line_start..]
+        .chars()
+        .take(err.column().saturating_sub(1))
+        .map(char::len_utf8)
+        .sum();
+
+    SchemaError::at_offset(source, offset + line_start + column, message)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "toml")]
+    mod toml {
+        use crate::schema::argument::ArgumentType;
+        use crate::schema::error::SchemaError;
+        use crate::schema::value::Value;
+        use crate::schema::Schema;
+
+        #[test]
+        fn should_load_schema_from_toml() {
+            // given
+            let source = r#"
+name = "mytool"
+description = "Does things"
+env_prefix = "MYTOOL"
+abbreviations = true
+
+[[options]]
+name = "verbose"
+short = "v"
+aliases = ["loud"]
+type = "bool"
+help = "Print more output"
+
+[[options]]
+name = "count"
+short = "n"
+type = "int"
+default = 1
+required = true
+env = "COUNT"
+"#;
+
+            // when
+            let schema = Schema::from_toml_str(source).unwrap();
+
+            // then
+            let verbose = schema.entry("verbose").unwrap();
+            assert_eq!(verbose.arg_type(), &ArgumentType::Bool);
+            assert_eq!(verbose.help(), Some("Print more output"));
+            assert_eq!(schema.str_to_argtype("-v"), Some(ArgumentType::Bool));
+            assert_eq!(schema.str_to_argtype("--loud"), Some(ArgumentType::Bool));
+            let count = schema.entry("count").unwrap();
+            assert_eq!(count.default(), Some(&Value::Int(1)));
+            assert!(count.is_required());
+            assert_eq!(schema.str_to_argtype("-n"), Some(ArgumentType::Int));
+            assert_eq!(schema.env_var(verbose), Some("MYTOOL_VERBOSE".to_string()));
+            assert_eq!(schema.env_var(count), Some("COUNT".to_string()));
+            assert!(schema.allows_abbreviations());
+            assert_eq!(schema.name(), Some("mytool"));
+            assert_eq!(schema.description(), Some("Does things"));
+        }
+
+        #[test]
+        fn should_report_position_of_unknown_type() {
+            // given
+            let source = "[[options]]\nname = \"a\"\ntype = \"float\"\n";
+
+            // when
+            let error = Schema::from_toml_str(source).unwrap_err();
+
+            // then
+            assert!(matches!(error, SchemaError::Parse { line: 3, column: 8, .. }), "{error}");
+        }
+
+        #[test]
+        fn should_report_position_of_mistyped_default() {
+            // given
+            let source = "[[options]]\nname = \"a\"\ntype = \"int\"\n\n[[options]]\nname = \"b\"\ntype = \"int\"\ndefault = \"one\"\n";
+
+            // when
+            let error = Schema::from_toml_str(source).unwrap_err();
+
+            // then
+            assert_eq!(error, SchemaError::Parse {
+                line: 5,
+                column: 1,
+                message: "default for `b` must be of type `int`".to_string(),
+            });
+        }
+
+        #[test]
+        fn should_report_syntax_errors() {
+            // given
+            let source = "[[options]\nname = \"a\"\n";
+
+            // when
+            let error = Schema::from_toml_str(source).unwrap_err();
+
+            // then
+            assert!(matches!(error, SchemaError::Parse { line: 1, .. }), "{error}");
+        }
+
+        #[test]
+        fn should_report_position_of_duplicate_name() {
+            // given
+            let source = "[[options]]\nname = \"a\"\ntype = \"int\"\n\n[[options]]\nname = \"a\"\ntype = \"bool\"\n";
+
+            // when
+            let error = Schema::from_toml_str(source).unwrap_err();
+
+            // then
+            assert_eq!(error, SchemaError::Parse {
+                line: 5,
+                column: 1,
+                message: "argument `a` is defined more than once".to_string(),
+            });
+        }
+    }
+
+    #[cfg(feature = "json")]
+    mod json {
+        use crate::schema::argument::ArgumentType;
+        use crate::schema::error::SchemaError;
+        use crate::schema::path::PathConstraint;
+        use crate::schema::value::Value;
+        use crate::schema::Schema;
+
+        #[test]
+        fn should_load_schema_from_json() {
+            // given
+            let source = r#"{
+  "options": [
+    {"name": "output", "short": "o", "type": "string", "default": "out.txt"},
+    {"name": "force", "long": "force", "aliases": ["f"], "type": "bool"}
+  ]
+}"#;
+
+            // when
+            let schema = Schema::from_json_str(source).unwrap();
+
+            // then
+            assert_eq!(schema.entry("output").unwrap().default(), Some(&Value::Str("out.txt".to_string())));
+            assert_eq!(schema.str_to_argtype("-o"), Some(ArgumentType::Str));
+            assert_eq!(schema.str_to_argtype("--output"), Some(ArgumentType::Str));
+            assert_eq!(schema.str_to_argtype("-f"), Some(ArgumentType::Bool));
+        }
+
+        #[test]
+        fn should_load_path_constraints() {
+            // given
+            let source = r#"{"options": [{"name": "input", "type": "path", "path_constraints": ["exists", "is_file"]}]}"#;
+
+            // when
+            let schema = Schema::from_json_str(source).unwrap();
+
+            // then
+            let input = schema.entry("input").unwrap();
+            assert_eq!(input.arg_type(), &ArgumentType::Path);
+            assert_eq!(input.path_constraints(), &[PathConstraint::Exists, PathConstraint::IsFile]);
+        }
+
+        #[test]
+        fn should_report_position_of_unknown_type() {
+            // given
+            let source = "{\"options\": [\n  {\"name\": \"a\",\n   \"type\": \"float\"}\n]}";
+
+            // when
+            let error = Schema::from_json_str(source).unwrap_err();
+
+            // then
+            assert!(matches!(error, SchemaError::Parse { line: 3, .. }), "{error}");
+            assert!(error.to_string().starts_with("unknown type `float`"), "{error}");
+        }
+
+        #[test]
+        fn should_report_unknown_fields() {
+            // given
+            let source = "{\"options\": [{\"name\": \"a\", \"typo\": \"int\"}]}";
+
+            // when
+            let error = Schema::from_json_str(source).unwrap_err();
+
+            // then
+            assert!(matches!(error, SchemaError::Parse { line: 1, .. }), "{error}");
+        }
+
+        #[test]
+        fn should_report_position_of_alias_collision() {
+            // given
+            let source = "{\"options\": [\n  {\"name\": \"verbose\", \"short\": \"v\", \"type\": \"bool\"},\n  {\"name\": \"version\", \"aliases\": [\"v\"], \"type\": \"bool\"}\n]}";
+
+            // when
+            let error = Schema::from_json_str(source).unwrap_err();
+
+            // then
+            assert_eq!(error, SchemaError::Parse {
+                line: 3,
+                column: 3,
+                message: "`-v` refers to both `verbose` and `version`".to_string(),
+            });
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GroupKind {
+    AtMostOne,
+    ExactlyOne,
+    AtLeastOne,
+}
+
+/// Options of a schema of which only some may be given together.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Group {
+    kind: GroupKind,
+    members: Vec<String>,
+}
+
+impl Group {
+    pub fn new(kind: GroupKind, members: &[&str]) -> Self {
+        Self {
+            kind,
+            members: members.iter().map(|member| member.to_string()).collect(),
+        }
+    }
+
+    pub fn kind(&self) -> GroupKind {
+        self.kind
+    }
+
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    pub fn allows(&self, count: usize) -> bool {
+        match self.kind {
+            GroupKind::AtMostOne => count <= 1,
+            GroupKind::ExactlyOne => count == 1,
+            GroupKind::AtLeastOne => count >= 1,
+        }
+    }
+}
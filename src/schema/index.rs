@@ -0,0 +1,121 @@
+use crate::schema::entry::Entry;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Index {
+    names: HashMap<String, usize>,
+    shorts: HashMap<char, usize>,
+    longs: HashMap<String, usize>,
+}
+
+impl Index {
+    pub(crate) fn new(entries: &[Entry]) -> Self {
+        let mut index = Self::default();
+
+        for (position, entry) in entries.iter().enumerate() {
+            index.names.insert(entry.name().to_string(), position);
+            if let Some(short) = entry.short() {
+                index.shorts.insert(short, position);
+            }
+            if let Some(long) = entry.long() {
+                index.longs.insert(long.to_string(), position);
+            }
+            for alias in entry.aliases() {
+                let mut chars = alias.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(short), None) => index.shorts.insert(short, position),
+                    _ => index.longs.insert(alias.to_string(), position),
+                };
+            }
+        }
+
+        index
+    }
+
+    pub(crate) fn name(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    pub(crate) fn short(&self, short: char) -> Option<usize> {
+        self.shorts.get(&short).copied()
+    }
+
+    pub(crate) fn arg(&self, arg: &str) -> Option<usize> {
+        if let Some(long) = arg.strip_prefix("--") {
+            return self.longs.get(long).copied();
+        }
+        let mut chars = arg.strip_prefix('-')?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(short), None) => self.short(short),
+            _ => None,
+        }
+    }
+
+    // Long spellings starting with `prefix`, sorted, grouped by the entry
+    // they belong to.
+    pub(crate) fn long_prefix(&self, prefix: &str) -> Vec<(usize, Vec<&str>)> {
+        let mut longs: Vec<(&str, usize)> = self.longs
+            .iter()
+            .filter(|(long, _)| long.starts_with(prefix))
+            .map(|(long, position)| (long.as_str(), *position))
+            .collect();
+        longs.sort();
+
+        let mut matches: Vec<(usize, Vec<&str>)> = Vec::new();
+        for (long, position) in longs {
+            match matches.iter_mut().find(|(other, _)| *other == position) {
+                Some((_, spellings)) => spellings.push(long),
+                None => matches.push((position, vec![long])),
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::argument::ArgumentType;
+
+    #[test]
+    fn should_index_names_shorts_longs_and_aliases() {
+        // given
+        let entries = vec![
+            Entry::new("b", ArgumentType::Bool),
+            Entry::new("verbose", ArgumentType::Bool).with_short('v').with_alias("V").with_alias("loud"),
+        ];
+
+        // when
+        let index = Index::new(&entries);
+
+        // then
+        assert_eq!(index.name("verbose"), Some(1));
+        assert_eq!(index.short('b'), Some(0));
+        assert_eq!(index.arg("-b"), Some(0));
+        assert_eq!(index.arg("-v"), Some(1));
+        assert_eq!(index.arg("-V"), Some(1));
+        assert_eq!(index.arg("--verbose"), Some(1));
+        assert_eq!(index.arg("--loud"), Some(1));
+        assert_eq!(index.arg("--b"), None);
+        assert_eq!(index.arg("-vb"), None);
+        assert_eq!(index.arg("verbose"), None);
+    }
+
+    #[test]
+    fn should_group_long_prefix_matches_by_entry() {
+        // given
+        let entries = vec![
+            Entry::new("verbose", ArgumentType::Bool).with_alias("verb"),
+            Entry::new("version", ArgumentType::Bool),
+            Entry::new("output", ArgumentType::Str),
+        ];
+
+        // when
+        let index = Index::new(&entries);
+
+        // then
+        assert_eq!(index.long_prefix("verb"), vec![(0, vec!["verb", "verbose"])]);
+        assert_eq!(index.long_prefix("ver"), vec![(0, vec!["verb", "verbose"]), (1, vec!["version"])]);
+        assert!(index.long_prefix("x").is_empty());
+    }
+}
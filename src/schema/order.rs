@@ -0,0 +1,9 @@
+/// Where options may appear relative to positional arguments.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum ArgumentOrder {
+    /// Options and positionals may be interleaved, as GNU tools allow.
+    #[default]
+    Permute,
+    /// The first positional ends option processing, as POSIX requires.
+    Posix,
+}
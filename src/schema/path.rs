@@ -0,0 +1,83 @@
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(any(feature = "toml", feature = "json"), derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum PathConstraint {
+    Exists,
+    IsFile,
+    IsDir,
+    NotExists,
+    ParentExists,
+}
+
+impl PathConstraint {
+    pub fn check(&self, path: &Path) -> bool {
+        match self {
+            PathConstraint::Exists => path.exists(),
+            PathConstraint::IsFile => path.is_file(),
+            PathConstraint::IsDir => path.is_dir(),
+            PathConstraint::NotExists => !path.exists(),
+            PathConstraint::ParentExists => match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.is_dir(),
+                _ => true,
+            },
+        }
+    }
+}
+
+impl fmt::Display for PathConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathConstraint::Exists => write!(f, "does not exist"),
+            PathConstraint::IsFile => write!(f, "is not a file"),
+            PathConstraint::IsDir => write!(f, "is not a directory"),
+            PathConstraint::NotExists => write!(f, "already exists"),
+            PathConstraint::ParentExists => write!(f, "has no existing parent directory"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn should_check_existence() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+
+        // then
+        assert!(PathConstraint::Exists.check(dir.path()));
+        assert!(!PathConstraint::Exists.check(&missing));
+        assert!(PathConstraint::NotExists.check(&missing));
+        assert!(!PathConstraint::NotExists.check(dir.path()));
+    }
+
+    #[test]
+    fn should_check_kind() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+
+        // then
+        assert!(PathConstraint::IsFile.check(&file));
+        assert!(!PathConstraint::IsFile.check(dir.path()));
+        assert!(PathConstraint::IsDir.check(dir.path()));
+        assert!(!PathConstraint::IsDir.check(&file));
+    }
+
+    #[test]
+    fn should_check_parent() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+
+        // then
+        assert!(PathConstraint::ParentExists.check(&dir.path().join("new.txt")));
+        assert!(!PathConstraint::ParentExists.check(&dir.path().join("missing/new.txt")));
+        assert!(PathConstraint::ParentExists.check(Path::new("relative.txt")));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::schema::argument::ArgumentType;
+use crate::schema::value::Value;
+use std::fmt;
+use std::sync::Arc;
+
+type Check = dyn Fn(&Value) -> Result<(), String> + Send + Sync;
+
+/// A rule that values of an entry must follow.
+#[derive(Clone)]
+pub enum Validator {
+    Min(i64),
+    Max(i64),
+    MinLength(usize),
+    MaxLength(usize),
+    NonEmpty,
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+    Custom(Arc<Check>),
+}
+
+impl Validator {
+    pub fn custom(check: impl Fn(&Value) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        Validator::Custom(Arc::new(check))
+    }
+
+    pub fn applies_to(&self, arg_type: &ArgumentType) -> bool {
+        match self {
+            Validator::Min(_) | Validator::Max(_) => *arg_type == ArgumentType::Int,
+            Validator::MinLength(_) | Validator::MaxLength(_) | Validator::NonEmpty => *arg_type == ArgumentType::Str,
+            #[cfg(feature = "regex")]
+            Validator::Regex(_) => *arg_type == ArgumentType::Str,
+            Validator::Custom(_) => true,
+        }
+    }
+
+    /// Checks `value`, returning why it was rejected otherwise.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        match (self, value) {
+            (Validator::Min(min), Value::Int(val)) if val < min => Err(format!("must be at least {min}")),
+            (Validator::Max(max), Value::Int(val)) if val > max => Err(format!("must be at most {max}")),
+            (Validator::MinLength(min), Value::Str(val)) if val.chars().count() < *min => {
+                Err(format!("must be at least {min} characters long"))
+            },
+            (Validator::MaxLength(max), Value::Str(val)) if val.chars().count() > *max => {
+                Err(format!("must be at most {max} characters long"))
+            },
+            (Validator::NonEmpty, Value::Str(val)) if val.is_empty() => Err("must not be empty".to_string()),
+            #[cfg(feature = "regex")]
+            (Validator::Regex(regex), Value::Str(val)) if !regex.is_match(val) => {
+                Err(format!("must match `{}`", regex.as_str()))
+            },
+            (Validator::Custom(check), value) => check(value),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for Validator {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Validator::Min(a), Validator::Min(b)) | (Validator::Max(a), Validator::Max(b)) => a == b,
+            (Validator::MinLength(a), Validator::MinLength(b)) | (Validator::MaxLength(a), Validator::MaxLength(b)) => a == b,
+            (Validator::NonEmpty, Validator::NonEmpty) => true,
+            #[cfg(feature = "regex")]
+            (Validator::Regex(a), Validator::Regex(b)) => a.as_str() == b.as_str(),
+            (Validator::Custom(a), Validator::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Validator::Min(min) => f.debug_tuple("Min").field(min).finish(),
+            Validator::Max(max) => f.debug_tuple("Max").field(max).finish(),
+            Validator::MinLength(min) => f.debug_tuple("MinLength").field(min).finish(),
+            Validator::MaxLength(max) => f.debug_tuple("MaxLength").field(max).finish(),
+            Validator::NonEmpty => f.write_str("NonEmpty"),
+            #[cfg(feature = "regex")]
+            Validator::Regex(regex) => f.debug_tuple("Regex").field(&regex.as_str()).finish(),
+            Validator::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_check_numeric_bounds() {
+        // given
+        let min = Validator::Min(1);
+        let max = Validator::Max(10);
+
+        // then
+        assert_eq!(min.check(&Value::Int(0)), Err("must be at least 1".to_string()));
+        assert_eq!(min.check(&Value::Int(1)), Ok(()));
+        assert_eq!(max.check(&Value::Int(11)), Err("must be at most 10".to_string()));
+    }
+
+    #[test]
+    fn should_count_length_in_characters() {
+        // given
+        let max = Validator::MaxLength(4);
+
+        // then
+        assert_eq!(max.check(&Value::Str("café".to_string())), Ok(()));
+        assert_eq!(max.check(&Value::Str("cafés".to_string())), Err("must be at most 4 characters long".to_string()));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn should_match_regex() {
+        // given
+        let validator = Validator::Regex(regex::Regex::new("^[a-z]+$").unwrap());
+
+        // then
+        assert_eq!(validator.check(&Value::Str("abc".to_string())), Ok(()));
+        assert_eq!(validator.check(&Value::Str("ABC".to_string())), Err("must match `^[a-z]+$`".to_string()));
+    }
+}
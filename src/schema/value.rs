@@ -0,0 +1,201 @@
+use crate::schema::argument::ArgumentType;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Bool(bool),
+    Str(String),
+    Int(i64),
+    OsStr(OsString),
+    Path(PathBuf),
+}
+
+impl Value {
+    pub fn parse(arg_type: &ArgumentType, string: &str) -> Option<Self> {
+        match arg_type {
+            ArgumentType::Bool => match string {
+                "" | "0" | "false" | "no" | "off" => Some(Value::Bool(false)),
+                "1" | "true" | "yes" | "on" => Some(Value::Bool(true)),
+                _ => None,
+            },
+            ArgumentType::Str => Some(Value::Str(string.to_string())),
+            ArgumentType::Int => string.parse().ok().map(Value::Int),
+            ArgumentType::OsStr => Some(Value::OsStr(string.into())),
+            ArgumentType::Path => Some(Value::Path(string.into())),
+        }
+    }
+
+    pub fn arg_type(&self) -> ArgumentType {
+        match self {
+            Value::Bool(_) => ArgumentType::Bool,
+            Value::Str(_) => ArgumentType::Str,
+            Value::Int(_) => ArgumentType::Int,
+            Value::OsStr(_) => ArgumentType::OsStr,
+            Value::Path(_) => ArgumentType::Path,
+        }
+    }
+}
+
+#[cfg(any(feature = "toml", feature = "json"))]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Bool(val) => serializer.serialize_bool(*val),
+            Value::Str(val) => serializer.serialize_str(val),
+            Value::Int(val) => serializer.serialize_i64(*val),
+            Value::OsStr(val) => serializer.serialize_str(&val.to_string_lossy()),
+            Value::Path(val) => serializer.serialize_str(&val.to_string_lossy()),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(val) => write!(f, "{val}"),
+            Value::Str(val) => write!(f, "{val}"),
+            Value::Int(val) => write!(f, "{val}"),
+            Value::OsStr(val) => write!(f, "{}", val.to_string_lossy()),
+            Value::Path(val) => write!(f, "{}", val.display()),
+        }
+    }
+}
+
+pub trait ArgumentValue: Into<Value> {
+    fn arg_type() -> ArgumentType;
+
+    fn from_value(value: &Value) -> Option<&Self>;
+}
+
+impl From<bool> for Value {
+    fn from(val: bool) -> Self {
+        Value::Bool(val)
+    }
+}
+
+impl From<String> for Value {
+    fn from(val: String) -> Self {
+        Value::Str(val)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(val: &str) -> Self {
+        Value::Str(val.to_string())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(val: i64) -> Self {
+        Value::Int(val)
+    }
+}
+
+impl From<OsString> for Value {
+    fn from(val: OsString) -> Self {
+        Value::OsStr(val)
+    }
+}
+
+impl From<PathBuf> for Value {
+    fn from(val: PathBuf) -> Self {
+        Value::Path(val)
+    }
+}
+
+impl ArgumentValue for bool {
+    fn arg_type() -> ArgumentType {
+        ArgumentType::Bool
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::Bool(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+impl ArgumentValue for String {
+    fn arg_type() -> ArgumentType {
+        ArgumentType::Str
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::Str(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+impl ArgumentValue for i64 {
+    fn arg_type() -> ArgumentType {
+        ArgumentType::Int
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::Int(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+impl ArgumentValue for OsString {
+    fn arg_type() -> ArgumentType {
+        ArgumentType::OsStr
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::OsStr(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+impl ArgumentValue for PathBuf {
+    fn arg_type() -> ArgumentType {
+        ArgumentType::Path
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::Path(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_return_matching_argument_type() {
+        assert_eq!(Value::Bool(true).arg_type(), ArgumentType::Bool);
+        assert_eq!(Value::Str("foo".to_string()).arg_type(), ArgumentType::Str);
+        assert_eq!(Value::Int(42).arg_type(), ArgumentType::Int);
+    }
+
+    #[test]
+    fn should_parse_values() {
+        assert_eq!(Value::parse(&ArgumentType::Int, "-42"), Some(Value::Int(-42)));
+        assert_eq!(Value::parse(&ArgumentType::Int, "forty"), None);
+        assert_eq!(Value::parse(&ArgumentType::Str, "foo"), Some(Value::Str("foo".to_string())));
+        assert_eq!(Value::parse(&ArgumentType::Bool, "yes"), Some(Value::Bool(true)));
+        assert_eq!(Value::parse(&ArgumentType::Bool, "0"), Some(Value::Bool(false)));
+        assert_eq!(Value::parse(&ArgumentType::Bool, "maybe"), None);
+    }
+
+    #[test]
+    fn should_convert_argument_values() {
+        assert_eq!(Value::from(true), Value::Bool(true));
+        assert_eq!(Value::from("foo".to_string()), Value::Str("foo".to_string()));
+        assert_eq!(Value::from(42), Value::Int(42));
+        assert_eq!(<i64 as ArgumentValue>::arg_type(), ArgumentType::Int);
+    }
+}
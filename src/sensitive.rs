@@ -0,0 +1,53 @@
+use std::fmt;
+
+pub const REDACTED: &str = "***";
+
+/// Wraps a value so that it never shows up in `Debug` output or
+/// serialized dumps.
+#[derive(PartialEq, Clone)]
+pub struct Sensitive<T>(T);
+
+impl<T> Sensitive<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+#[cfg(any(feature = "toml", feature = "json"))]
+impl<T> serde::Serialize for Sensitive<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_redact_debug_output() {
+        // given
+        let secret = Sensitive::new("hunter2".to_string());
+
+        // when
+        let debug = format!("{secret:?}");
+
+        // then
+        assert_eq!(debug, "***");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+}
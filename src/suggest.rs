@@ -0,0 +1,91 @@
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Returns the candidates closest to `input`, best match first. A candidate
+/// qualifies when at most a third of the longer spelling has to be edited.
+pub(crate) fn suggest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let input: Vec<char> = input.chars().collect();
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let chars: Vec<char> = candidate.chars().collect();
+            let distance = distance(&input, &chars);
+            (distance <= input.len().max(chars.len()) / 3).then_some((distance, candidate))
+        })
+        .collect();
+
+    matches.sort();
+    matches.dedup();
+    matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+// Optimal string alignment distance: Levenshtein plus transpositions of
+// adjacent characters, the most common typo on the command line.
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_suggest_close_candidates_first() {
+        // given
+        let candidates = ["--verbose", "--version", "--output", "-v"];
+
+        // when
+        let suggestions = suggest("--verbos", candidates);
+
+        // then
+        assert_eq!(suggestions, vec!["--verbose".to_string(), "--version".to_string()]);
+    }
+
+    #[test]
+    fn should_count_transpositions_as_single_edit() {
+        // given
+        let candidates = ["stash", "status"];
+
+        // when
+        let suggestions = suggest("statsu", candidates);
+
+        // then
+        assert_eq!(suggestions, vec!["status".to_string(), "stash".to_string()]);
+    }
+
+    #[test]
+    fn should_not_suggest_for_unrelated_short_options() {
+        // given
+        let candidates = ["-v", "-o"];
+
+        // when
+        let suggestions = suggest("-x", candidates);
+
+        // then
+        assert!(suggestions.is_empty());
+    }
+}
@@ -1,4 +1,8 @@
 use crate::schema::argument::ArgumentType;
+use crate::sensitive::Sensitive;
+use std::ffi::OsString;
+use std::ops::Range;
+use std::path::PathBuf;
 
 type Name = String;
 
@@ -6,9 +10,26 @@ type Name = String;
 pub enum Token {
     AppName,
     Argument(ArgumentType, Name),
+    Subcommand(Name),
     StrValue(String),
     IntValue(i64),
+    OsStrValue(OsString),
+    PathValue(PathBuf),
+    Sensitive(Sensitive<Box<Token>>),
+    Unknown(OsString),
+    Positional(OsString),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Span {
+    pub index: usize,
+    pub range: Range<usize>,
+    // The response file and line of an argument read from one, in which
+    // case `index` is that of the `@file` argument.
+    pub file: Option<(PathBuf, usize)>,
 }
 
 pub mod tokens;
 pub mod parser;
+mod response;
+pub mod split;
@@ -1,14 +1,23 @@
+use crate::error::Error;
 use crate::schema::Schema;
+use crate::token::response::{self, ArgSource};
 use crate::token::tokens::Tokens;
+use crate::token::{Span, Token};
+use std::ffi::{OsStr, OsString};
+use std::ops::Range;
 use crate::token::parser::strategy::ParserStrategy;
 
 #[derive(Default)]
 pub struct TokenParser {
-    args: Vec<String>,
+    args: Vec<OsString>,
+    sources: Vec<ArgSource>,
     index: usize,
     schema: Option<Schema>,
     strategy: Box<dyn ParserStrategy>,
     tokens: Tokens,
+    response_files: bool,
+    commands: Vec<String>,
+    posixly_correct: bool,
 }
 
 mod strategy;
@@ -20,7 +29,14 @@ impl TokenParser {
 
     pub fn args(self, args: Vec<&str>) -> Self {
         Self {
-            args: args.into_iter().map(String::from).collect(),
+            args: args.into_iter().map(OsString::from).collect(),
+            ..self
+        }
+    }
+
+    pub fn args_os(self, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
             ..self
         }
     }
@@ -32,28 +48,61 @@ impl TokenParser {
         }
     }
 
+    pub fn response_files(self, response_files: bool) -> Self {
+        Self {
+            response_files,
+            ..self
+        }
+    }
+
+    /// Stops option processing at the first positional, whatever the order
+    /// of the schema.
+    pub fn posixly_correct(self, posixly_correct: bool) -> Self {
+        Self {
+            posixly_correct,
+            ..self
+        }
+    }
+
     pub fn collect(self) -> Tokens {
+        self.try_collect().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_collect(self) -> Result<Tokens, Error> {
         let mut parser = self;
+        if parser.response_files {
+            let schema = parser.schema.clone().unwrap_or_default();
+            let expanded = response::expand(parser.args, &|arg| reads_input(&schema, arg))?;
+            (parser.args, parser.sources) = expanded.into_iter().unzip();
+        }
         while !parser.is_done() {
-            parser = parser.parse_current();
+            parser = parser.parse_current()?;
             parser.next();
         }
-        if parser.schema.is_some() {
-            parser.tokens.schema_set(parser.schema.unwrap());
+        if let Some(schema) = parser.schema {
+            parser.tokens.schema_set(schema);
         }
 
-        parser.tokens
+        Ok(parser.tokens)
     }
 
     pub fn set_strategy(&mut self, strategy: Box<dyn ParserStrategy>) {
         self.strategy = strategy;
     }
 
+    // The schema of the innermost subcommand entered so far.
+    fn active_schema(&self) -> &Schema {
+        let root = self.schema.as_ref().expect("Expected schema");
+        self.commands.iter().fold(root, |schema, name| {
+            schema.subcommand(name).expect("Entered subcommands are defined")
+        })
+    }
+
     fn is_done(&self) -> bool {
         self.index >= self.args.len()
     }
 
-    fn parse_current(self) -> Self {
+    fn parse_current(self) -> Result<Self, Error> {
         let strategy = self.strategy.clone();
         strategy.parse(self)
     }
@@ -62,16 +111,44 @@ impl TokenParser {
         self.index += 1;
     }
     
-    fn current_arg(&self) -> &str {
+    fn current_arg(&self) -> &OsStr {
         self.args.get(self.index).expect("Index should be valid")
     }
+
+    fn add_token(&mut self, token: Token, range: Range<usize>) {
+        let span = match self.sources.get(self.index) {
+            Some(source) => Span {
+                index: source.index,
+                range,
+                file: source.file.clone(),
+            },
+            None => Span {
+                index: self.index,
+                range,
+                file: None,
+            },
+        };
+        self.tokens.add_spanned(token, span);
+    }
+}
+
+// Whether `arg` is an option of the schema, or of one of its subcommands,
+// whose value may name a file for the entry to read.
+fn reads_input(schema: &Schema, arg: &OsStr) -> bool {
+    let Some(option) = arg.to_str() else {
+        return false;
+    };
+
+    matches!(schema.resolve_abbreviated(option), Ok(Some(entry)) if entry.reads_input())
+        || schema.subcommands().any(|(_, subcommand)| reads_input(subcommand, arg))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token::Token;
     use crate::schema::argument::ArgumentType;
+    use crate::schema::entry::Entry;
+    use crate::schema::order::ArgumentOrder;
 
     #[test]
     fn should_return_empty_token_collection_when_no_args() {
@@ -178,5 +255,408 @@ mod tests {
         assert_eq!(tokens.get(4), &Token::Argument(ArgumentType::Str, "s".to_string()));
         assert_eq!(tokens.get(5), &Token::StrValue("string".to_string()));
     }
-}
 
+    #[test]
+    fn should_get_argument_by_long_name() {
+        // given
+        let schema = Schema::from_entries(vec![
+            Entry::new("verbose", ArgumentType::Bool).with_short('v'),
+            Entry::new("count", ArgumentType::Int).with_short('n'),
+        ]);
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--verbose", "-n", "3"])
+            .schema(schema);
+
+        // when
+        let tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.size(), 4);
+        assert_eq!(tokens.get(1), &Token::Argument(ArgumentType::Bool, "verbose".to_string()));
+        assert_eq!(tokens.get(2), &Token::Argument(ArgumentType::Int, "count".to_string()));
+        assert_eq!(tokens.get(3), &Token::IntValue(3));
+    }
+
+    #[test]
+    fn should_split_long_argument_with_value() {
+        // given
+        let schema = Schema::from_entries(vec![
+            Entry::new("count", ArgumentType::Int),
+            Entry::new("output", ArgumentType::Str),
+        ]);
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--count=3", "--output=a=b"])
+            .schema(schema);
+
+        // when
+        let mut tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.size(), 5);
+        assert_eq!(tokens.get(1), &Token::Argument(ArgumentType::Int, "count".to_string()));
+        assert_eq!(tokens.get(2), &Token::IntValue(3));
+        assert_eq!(tokens.get(3), &Token::Argument(ArgumentType::Str, "output".to_string()));
+        assert_eq!(tokens.get(4), &Token::StrValue("a=b".to_string()));
+        tokens.next();
+        tokens.next();
+        assert_eq!(tokens.current_span(), Some(&Span { index: 1, range: 8..9, file: None }));
+        tokens.next();
+        tokens.next();
+        assert_eq!(tokens.current_span(), Some(&Span { index: 2, range: 9..12, file: None }));
+    }
+
+    #[test]
+    fn should_record_spans_of_arguments() {
+        // given
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "-s", "string"])
+            .schema(schema);
+
+        // when
+        let mut tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.current_span(), Some(&Span { index: 0, range: 0..8, file: None }));
+        tokens.next();
+        assert_eq!(tokens.current_span(), Some(&Span { index: 1, range: 0..2, file: None }));
+        tokens.next();
+        assert_eq!(tokens.current_span(), Some(&Span { index: 2, range: 0..6, file: None }));
+    }
+
+    #[test]
+    fn should_expand_response_files_when_enabled() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        std::fs::write(&file, "-s 'a b'").unwrap();
+        let at_file = format!("@{}", file.display());
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+        let parser = TokenParser::new()
+            .args(vec!["app_name", &at_file])
+            .schema(schema)
+            .response_files(true);
+
+        // when
+        let tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.size(), 3);
+        assert_eq!(tokens.get(2), &Token::StrValue("a b".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expects valid argument")]
+    fn should_not_expand_response_files_by_default() {
+        // given
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "@args.txt"])
+            .schema(schema);
+
+        // when
+        parser.collect();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_preserve_non_utf8_os_str_values() {
+        use std::os::unix::ffi::OsStrExt;
+
+        // given
+        let schema = Schema::from_entries(vec![
+            Entry::new("file", ArgumentType::OsStr),
+        ]);
+        let raw = OsStr::from_bytes(b"caf\xe9.txt");
+        let mut long = OsString::from("--file=");
+        long.push(raw);
+        let parser = TokenParser::new()
+            .args_os([OsStr::new("app_name"), OsStr::new("--file"), raw, &long])
+            .schema(schema);
+
+        // when
+        let tokens = parser.try_collect().unwrap();
+
+        // then
+        assert_eq!(tokens.size(), 5);
+        assert_eq!(tokens.get(2), &Token::OsStrValue(raw.to_os_string()));
+        assert_eq!(tokens.get(4), &Token::OsStrValue(raw.to_os_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_reject_non_utf8_string_values() {
+        use std::os::unix::ffi::OsStrExt;
+
+        // given
+        let schema = Schema::from(vec![
+            ("s".to_string(), "string".to_string()),
+        ]);
+        let parser = TokenParser::new()
+            .args_os([OsStr::new("app_name"), OsStr::new("-s"), OsStr::from_bytes(b"caf\xe9")])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert_eq!(result.err(), Some(Error::InvalidUtf8 {
+            name: "s".to_string(),
+            value: "caf\u{FFFD}".to_string(),
+        }));
+    }
+
+    #[test]
+    fn should_reject_invalid_numbers() {
+        // given
+        let schema = Schema::from(vec![
+            ("i".to_string(), "int".to_string()),
+        ]);
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "-i", "forty"])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert_eq!(result.err().map(|err| err.to_string()), Some("Invalid int for i: forty".to_string()));
+    }
+
+    #[test]
+    fn should_suggest_closest_options_for_unknown_argument() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .option::<String>('o', "output")
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--verbsoe"])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Expects valid argument: --verbsoe (did you mean --verbose?)".to_string()),
+        );
+    }
+
+    #[test]
+    fn should_parse_arguments_of_subcommand() {
+        // given
+        let remote = Schema::builder()
+            .flag('v', "verbose")
+            .build()
+            .unwrap();
+        let schema = Schema::builder()
+            .flag('q', "quiet")
+            .subcommand("remote", remote)
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "-q", "remote", "-v"])
+            .schema(schema);
+
+        // when
+        let tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.size(), 4);
+        assert_eq!(tokens.get(2), &Token::Subcommand("remote".to_string()));
+        assert_eq!(tokens.get(3), &Token::Argument(ArgumentType::Bool, "verbose".to_string()));
+    }
+
+    #[test]
+    fn should_suggest_closest_subcommand() {
+        // given
+        let schema = Schema::builder()
+            .subcommand("status", Schema::new())
+            .subcommand("stash", Schema::new())
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "statsu"])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert_eq!(result.err(), Some(Error::UnknownArgument {
+            arg: "statsu".to_string(),
+            suggestions: vec!["status".to_string(), "stash".to_string()],
+        }));
+    }
+
+    #[test]
+    fn should_resolve_unambiguous_long_prefix_when_enabled() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .flag('V', "version")
+            .option::<i64>('n', "count")
+            .abbreviations(true)
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--verb", "--co=3"])
+            .schema(schema);
+
+        // when
+        let tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.get(1), &Token::Argument(ArgumentType::Bool, "verbose".to_string()));
+        assert_eq!(tokens.get(2), &Token::Argument(ArgumentType::Int, "count".to_string()));
+        assert_eq!(tokens.get(3), &Token::IntValue(3));
+    }
+
+    #[test]
+    fn should_report_ambiguous_long_prefix() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .flag('V', "version")
+            .abbreviations(true)
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--ver"])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Ambiguous argument: --ver could be --verbose, --version".to_string()),
+        );
+    }
+
+    #[test]
+    fn should_not_resolve_prefixes_by_default() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--verb"])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert!(matches!(result, Err(Error::UnknownArgument { .. })));
+    }
+
+    #[test]
+    fn should_collect_unknown_options_with_their_values_when_enabled() {
+        // given
+        let schema = Schema::builder()
+            .flag('v', "verbose")
+            .collect_unknown(true)
+            .subcommand("run", Schema::new())
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--level", "3", "-x", "-v", "--mode=fast", "--dry", "run"])
+            .schema(schema);
+
+        // when
+        let tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.get(1), &Token::Unknown("--level".into()));
+        assert_eq!(tokens.get(2), &Token::Unknown("3".into()));
+        assert_eq!(tokens.get(3), &Token::Unknown("-x".into()));
+        assert_eq!(tokens.get(4), &Token::Argument(ArgumentType::Bool, "verbose".to_string()));
+        assert_eq!(tokens.get(5), &Token::Unknown("--mode=fast".into()));
+        assert_eq!(tokens.get(6), &Token::Unknown("--dry".into()));
+        assert_eq!(tokens.get(7), &Token::Subcommand("run".to_string()));
+    }
+
+    #[test]
+    fn should_not_take_value_for_unknown_option_with_attached_value() {
+        // given
+        let schema = Schema::builder()
+            .collect_unknown(true)
+            .build()
+            .unwrap();
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "--dry", "--mode=fast", "cmd"])
+            .schema(schema);
+
+        // when
+        let result = parser.try_collect();
+
+        // then
+        assert!(matches!(result.err(), Some(Error::UnknownArgument { arg, .. }) if arg == "cmd"));
+    }
+
+    fn timeout_schema(order: ArgumentOrder) -> Schema {
+        Schema::builder()
+            .option::<String>('s', "signal")
+            .flag('v', "verbose")
+            .positionals(true)
+            .order(order)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_interleave_options_and_positionals_when_permuting() {
+        // given
+        let parser = TokenParser::new()
+            .args(vec!["app_name", "cmd", "-v", "-", "--", "-x"])
+            .schema(timeout_schema(ArgumentOrder::Permute));
+
+        // when
+        let tokens = parser.collect();
+
+        // then
+        assert_eq!(tokens.size(), 5);
+        assert_eq!(tokens.get(1), &Token::Positional("cmd".into()));
+        assert_eq!(tokens.get(2), &Token::Argument(ArgumentType::Bool, "verbose".to_string()));
+        assert_eq!(tokens.get(3), &Token::Positional("-".into()));
+        assert_eq!(tokens.get(4), &Token::Positional("-x".into()));
+    }
+
+    #[test]
+    fn should_stop_option_processing_at_first_positional_in_posix_order() {
+        // given
+        let args = vec!["app_name", "-s", "KILL", "cmd", "-x", "-v"];
+        let posix = TokenParser::new()
+            .args(args.clone())
+            .schema(timeout_schema(ArgumentOrder::Posix));
+        let posixly_correct = TokenParser::new()
+            .args(args)
+            .schema(timeout_schema(ArgumentOrder::Permute))
+            .posixly_correct(true);
+
+        // when
+        let posix = posix.collect();
+        let posixly_correct = posixly_correct.collect();
+
+        // then
+        for tokens in [posix, posixly_correct] {
+            assert_eq!(tokens.size(), 6);
+            assert_eq!(tokens.get(2), &Token::StrValue("KILL".to_string()));
+            assert_eq!(tokens.get(3), &Token::Positional("cmd".into()));
+            assert_eq!(tokens.get(4), &Token::Positional("-x".into()));
+            assert_eq!(tokens.get(5), &Token::Positional("-v".into()));
+        }
+    }
+}
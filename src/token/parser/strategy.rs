@@ -1,10 +1,18 @@
+use crate::error::Error;
 use crate::token::parser::TokenParser;
 use crate::token::Token;
 use crate::schema::argument::ArgumentType;
+use crate::schema::order::ArgumentOrder;
+use crate::schema::value::Value;
+use crate::schema::Schema;
+use crate::sensitive::Sensitive;
+use crate::suggest::suggest;
 use dyn_clone::{clone_trait_object, DynClone};
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
 
 pub trait ParserStrategy: DynClone {
-    fn parse(&self, parser: TokenParser) -> TokenParser;
+    fn parse(&self, parser: TokenParser) -> Result<TokenParser, Error>;
 }
 
 clone_trait_object!(ParserStrategy);
@@ -16,68 +24,283 @@ struct InitParser;
 struct ArgumentParser;
 
 #[derive(Clone)]
-struct StrParser;
+struct StrParser {
+    name: String,
+}
 
 #[derive(Clone)]
-struct IntParser;
+struct IntParser {
+    name: String,
+}
+
+#[derive(Clone)]
+struct OsStrParser;
+
+#[derive(Clone)]
+struct PathParser;
+
+// Takes every remaining argument as a positional, once option processing
+// has ended.
+#[derive(Clone)]
+struct PositionalParser;
+
+// Takes the argument after an unknown option as its value, unless it looks
+// like an option or a subcommand itself, or could be a positional.
+#[derive(Clone)]
+struct UnknownValueParser;
+
+// Decorates the value parser of a sensitive entry so that neither its
+// token nor an error about its value reveals what was passed.
+#[derive(Clone)]
+struct SensitiveParser {
+    inner: Box<dyn ParserStrategy>,
+}
 
 impl ParserStrategy for InitParser {
-    fn parse(&self, mut parser: TokenParser) -> TokenParser {
-        parser.tokens.add(Token::AppName);
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let len = parser.current_arg().len();
+        parser.add_token(Token::AppName, 0..len);
         parser.set_strategy(Box::new(ArgumentParser));
-        parser
+        Ok(parser)
     }
 }
 
 impl ParserStrategy for ArgumentParser {
-    fn parse(&self, mut parser: TokenParser) -> TokenParser {
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
         let arg = parser.current_arg().to_owned();
-        let arg_type = parser.schema
-            .as_ref()
-            .expect("Expected schema")
-            .str_to_argtype(&arg)
-            .expect("Expects valid argument");
+        let schema = parser.active_schema();
+        if let Some(name) = arg.to_str().filter(|name| schema.subcommand(name).is_some()) {
+            parser.add_token(Token::Subcommand(name.to_string()), 0..name.len());
+            parser.commands.push(name.to_string());
+            return Ok(parser);
+        }
+        if schema.accepts_positionals() && (arg == "--" || !is_option(&arg)) {
+            return Ok(positional(parser, arg));
+        }
 
-        let strategy: Box<dyn ParserStrategy> = match arg_type {
+        let (option, value) = split_long_value(&arg);
+        let option = option
+            .to_str()
+            .ok_or_else(|| Error::UnknownArgument {
+                arg: option.to_string_lossy().into_owned(),
+                suggestions: Vec::new(),
+            })?;
+        let entry = schema
+            .resolve_abbreviated(option)
+            .map_err(|candidates| Error::AmbiguousArgument {
+                arg: option.to_string(),
+                candidates,
+            })?;
+        let Some(entry) = entry else {
+            if !(schema.collects_unknown() && is_option(&arg)) {
+                return Err(unknown_argument(option, schema));
+            }
+            if value.is_none() {
+                parser.set_strategy(Box::new(UnknownValueParser));
+            }
+            parser.add_token(Token::Unknown(arg.clone()), 0..arg.len());
+            return Ok(parser);
+        };
+        let arg_type = entry.arg_type().clone();
+        let name = entry.name().to_owned();
+        let sensitive = entry.is_sensitive() && arg_type != ArgumentType::Bool;
+
+        let mut strategy: Box<dyn ParserStrategy> = match arg_type {
             ArgumentType::Bool => Box::new(ArgumentParser),
-            ArgumentType::Int => Box::new(IntParser),
-            ArgumentType::Str => Box::new(StrParser),
+            ArgumentType::Int => Box::new(IntParser { name: name.clone() }),
+            ArgumentType::Str => Box::new(StrParser { name: name.clone() }),
+            ArgumentType::OsStr => Box::new(OsStrParser),
+            ArgumentType::Path => Box::new(PathParser),
         };
+        if sensitive {
+            strategy = Box::new(SensitiveParser { inner: strategy });
+        }
 
         parser.set_strategy(strategy);
-        parser.tokens.add(Token::Argument(arg_type, arg[1..].to_owned()));
+        parser.add_token(Token::Argument(arg_type.clone(), name.clone()), 0..option.len());
+        parser.tokens.set_last_spelling(option);
 
-        parser
+        if let Some(value) = value {
+            let token = match arg_type {
+                ArgumentType::Bool => return Err(Error::UnexpectedValue(option.to_string())),
+                ArgumentType::Int => int_token(&name, value),
+                ArgumentType::Str => str_token(&name, value),
+                ArgumentType::OsStr => Ok(os_str_token(value)),
+                ArgumentType::Path => Ok(path_token(value)),
+            };
+            let token = if sensitive {
+                Token::Sensitive(Sensitive::new(Box::new(token.map_err(Error::redacted)?)))
+            } else {
+                token?
+            };
+            parser.add_token(token, option.len() + 1..arg.len());
+            parser.set_strategy(Box::new(ArgumentParser));
+        }
+
+        Ok(parser)
     }
 }
 
 impl ParserStrategy for StrParser {
-    fn parse(&self, mut parser: TokenParser) -> TokenParser {
-        let string_value = parser.current_arg().to_string();
-        let token = Token::StrValue(string_value);
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let token = str_token(&self.name, parser.current_arg())?;
+        let len = parser.current_arg().len();
 
-        parser.tokens.add(token);
+        parser.add_token(token, 0..len);
 
         parser.set_strategy(Box::new(ArgumentParser));
-        parser
+        Ok(parser)
     }
 }
 
 impl ParserStrategy for IntParser {
-    fn parse(&self, mut parser: TokenParser) -> TokenParser {
-        let int_value = parser.current_arg().parse().expect("Valid number expected");
-        let token = Token::IntValue(int_value);
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let token = int_token(&self.name, parser.current_arg())?;
+        let len = parser.current_arg().len();
+
+        parser.add_token(token, 0..len);
+
+        parser.set_strategy(Box::new(ArgumentParser));
+        Ok(parser)
+    }
+}
+
+impl ParserStrategy for OsStrParser {
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let token = os_str_token(parser.current_arg());
+        let len = parser.current_arg().len();
+
+        parser.add_token(token, 0..len);
+
+        parser.set_strategy(Box::new(ArgumentParser));
+        Ok(parser)
+    }
+}
+
+impl ParserStrategy for PathParser {
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let token = path_token(parser.current_arg());
+        let len = parser.current_arg().len();
+
+        parser.add_token(token, 0..len);
+
+        parser.set_strategy(Box::new(ArgumentParser));
+        Ok(parser)
+    }
+}
+
+impl ParserStrategy for PositionalParser {
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let arg = parser.current_arg().to_owned();
+        parser.add_token(Token::Positional(arg.clone()), 0..arg.len());
+        Ok(parser)
+    }
+}
 
-        parser.tokens.add(token);
+impl ParserStrategy for UnknownValueParser {
+    fn parse(&self, mut parser: TokenParser) -> Result<TokenParser, Error> {
+        let arg = parser.current_arg().to_owned();
+        let is_subcommand = arg
+            .to_str()
+            .is_some_and(|arg| parser.active_schema().subcommand(arg).is_some());
+        if is_option(&arg) || is_subcommand || parser.active_schema().accepts_positionals() {
+            parser.set_strategy(Box::new(ArgumentParser));
+            return ArgumentParser.parse(parser);
+        }
 
+        parser.add_token(Token::Unknown(arg.clone()), 0..arg.len());
         parser.set_strategy(Box::new(ArgumentParser));
-        parser
+        Ok(parser)
+    }
+}
+
+impl ParserStrategy for SensitiveParser {
+    fn parse(&self, parser: TokenParser) -> Result<TokenParser, Error> {
+        let mut parser = self.inner.parse(parser).map_err(Error::redacted)?;
+        parser.tokens.mark_last_sensitive();
+        Ok(parser)
+    }
+}
+
+fn unknown_argument(arg: &str, schema: &Schema) -> Error {
+    let spellings = schema.spellings();
+
+    Error::UnknownArgument {
+        arg: arg.to_string(),
+        suggestions: suggest(arg, spellings.iter().map(String::as_str)),
+    }
+}
+
+// Adds a positional, ending option processing when the order asks for it.
+// A `--` only ends option processing.
+fn positional(mut parser: TokenParser, arg: OsString) -> TokenParser {
+    if arg == "--" {
+        parser.set_strategy(Box::new(PositionalParser));
+        return parser;
+    }
+
+    parser.add_token(Token::Positional(arg.clone()), 0..arg.len());
+    if parser.posixly_correct || parser.active_schema().order() == ArgumentOrder::Posix {
+        parser.set_strategy(Box::new(PositionalParser));
+    }
+    parser
+}
+
+fn is_option(arg: &OsStr) -> bool {
+    arg.len() > 1 && arg.as_encoded_bytes().starts_with(b"-")
+}
+
+fn split_long_value(arg: &OsStr) -> (&OsStr, Option<&OsStr>) {
+    let bytes = arg.as_encoded_bytes();
+    if !bytes.starts_with(b"--") {
+        return (arg, None);
+    }
+    match bytes.iter().position(|byte| *byte == b'=') {
+        // SAFETY: both halves are split right next to the ASCII `=`, which is
+        // a valid UTF-8 substring, so they remain valid encoded `OsStr`s.
+        Some(position) => unsafe {
+            (
+                OsStr::from_encoded_bytes_unchecked(&bytes[..position]),
+                Some(OsStr::from_encoded_bytes_unchecked(&bytes[position + 1..])),
+            )
+        },
+        None => (arg, None),
     }
 }
 
+fn utf8<'a>(name: &str, value: &'a OsStr) -> Result<&'a str, Error> {
+    value.to_str().ok_or_else(|| Error::InvalidUtf8 {
+        name: name.to_string(),
+        value: value.to_string_lossy().into_owned(),
+    })
+}
+
+fn str_token(name: &str, value: &OsStr) -> Result<Token, Error> {
+    Ok(Token::StrValue(utf8(name, value)?.to_string()))
+}
+
+fn int_token(name: &str, value: &OsStr) -> Result<Token, Error> {
+    let string = utf8(name, value)?;
+    match Value::parse(&ArgumentType::Int, string) {
+        Some(Value::Int(val)) => Ok(Token::IntValue(val)),
+        _ => Err(Error::InvalidValue {
+            name: name.to_string(),
+            arg_type: ArgumentType::Int,
+            value: string.to_string(),
+        }),
+    }
+}
+
+fn os_str_token(value: &OsStr) -> Token {
+    Token::OsStrValue(value.to_os_string())
+}
+
+fn path_token(value: &OsStr) -> Token {
+    Token::PathValue(PathBuf::from(value))
+}
+
 impl Default for Box<dyn ParserStrategy> {
     fn default() -> Self {
         Box::new(InitParser)
     }
 }
-
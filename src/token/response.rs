@@ -0,0 +1,215 @@
+use crate::error::Error;
+use crate::token::split::split_lines;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Where an expanded argument came from: the index of the argument on the
+// command line and, for words read from a response file, that file and the
+// line of the word.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArgSource {
+    pub index: usize,
+    pub file: Option<(PathBuf, usize)>,
+}
+
+// Arguments right after an option for which `literal` holds are kept as
+// they are, so that entries reading `@path` themselves receive it.
+pub fn expand(args: Vec<OsString>, literal: &dyn Fn(&OsStr) -> bool) -> Result<Vec<(OsString, ArgSource)>, Error> {
+    let mut expanded = Vec::new();
+
+    for (index, arg) in args.into_iter().enumerate() {
+        let source = ArgSource { index, file: None };
+        match index {
+            0 => expanded.push((arg, source)),
+            _ => expand_arg(arg, source, Path::new(""), literal, &mut Vec::new(), &mut expanded)?,
+        }
+    }
+
+    Ok(expanded)
+}
+
+fn expand_arg(
+    arg: OsString,
+    source: ArgSource,
+    base: &Path,
+    literal: &dyn Fn(&OsStr) -> bool,
+    stack: &mut Vec<PathBuf>,
+    expanded: &mut Vec<(OsString, ArgSource)>,
+) -> Result<(), Error> {
+    let after_literal = expanded.len() > 1 && expanded.last().is_some_and(|(previous, _)| literal(previous));
+    let path = match strip_at(&arg) {
+        Some(path) if !path.is_empty() && !after_literal => base.join(path),
+        _ => {
+            expanded.push((arg, source));
+            return Ok(());
+        },
+    };
+    let error = |line, message: String| Error::ResponseFile {
+        path: path.clone(),
+        line,
+        message,
+    };
+
+    let canonical = fs::canonicalize(&path).map_err(|err| error(None, err.to_string()))?;
+    if stack.contains(&canonical) {
+        return Err(error(None, "Response file includes itself".to_string()));
+    }
+    let contents = fs::read_to_string(&path).map_err(|err| error(None, err.to_string()))?;
+    let words = split_lines(&contents).map_err(|err| error(Some(err.line), err.message))?;
+
+    stack.push(canonical);
+    let base = path.parent().unwrap_or(Path::new(""));
+    for (word, line) in words {
+        let source = ArgSource {
+            index: source.index,
+            file: Some((path.clone(), line)),
+        };
+        expand_arg(word.into(), source, base, literal, stack, expanded)?;
+    }
+    stack.pop();
+
+    Ok(())
+}
+
+fn strip_at(arg: &OsStr) -> Option<&OsStr> {
+    let bytes = arg.as_encoded_bytes().strip_prefix(b"@")?;
+
+    // SAFETY: the bytes follow the ASCII `@`, which is a valid UTF-8
+    // substring, so they remain a valid encoded `OsStr`.
+    Some(unsafe { OsStr::from_encoded_bytes_unchecked(bytes) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    fn words(expanded: Vec<(OsString, ArgSource)>) -> Vec<OsString> {
+        expanded.into_iter().map(|(word, _)| word).collect()
+    }
+
+    #[test]
+    fn should_expand_response_file() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        fs::write(&file, "-s 'a b'\n# comment\n-b\n").unwrap();
+        let at_file = format!("@{}", file.display());
+
+        // when
+        let expanded = words(expand(args(&["app_name", "-i", "1", &at_file, "-x"]), &|_| false).unwrap());
+
+        // then
+        assert_eq!(expanded, args(&["app_name", "-i", "1", "-s", "a b", "-b", "-x"]));
+    }
+
+    #[test]
+    fn should_not_expand_app_name_or_lone_at() {
+        // when
+        let expanded = words(expand(args(&["@app", "@"]), &|_| false).unwrap());
+
+        // then
+        assert_eq!(expanded, args(&["@app", "@"]));
+    }
+
+    #[test]
+    fn should_expand_nested_files_relative_to_parent() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("outer.txt"), "-a @nested/inner.txt -c").unwrap();
+        fs::write(dir.path().join("nested/inner.txt"), "-b").unwrap();
+        let at_file = format!("@{}", dir.path().join("outer.txt").display());
+
+        // when
+        let expanded = words(expand(args(&["app_name", &at_file]), &|_| false).unwrap());
+
+        // then
+        assert_eq!(expanded, args(&["app_name", "-a", "-b", "-c"]));
+    }
+
+    #[test]
+    fn should_detect_cycles() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "-a @b.txt").unwrap();
+        fs::write(dir.path().join("b.txt"), "-b @a.txt").unwrap();
+        let at_file = format!("@{}", dir.path().join("a.txt").display());
+
+        // when
+        let error = expand(args(&["app_name", &at_file]), &|_| false).unwrap_err();
+
+        // then
+        assert_eq!(error, Error::ResponseFile {
+            path: dir.path().join("a.txt"),
+            line: None,
+            message: "Response file includes itself".to_string(),
+        });
+    }
+
+    #[test]
+    fn should_report_file_and_line_of_split_errors() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        fs::write(&file, "-a\n-s \"unterminated\n").unwrap();
+        let at_file = format!("@{}", file.display());
+
+        // when
+        let error = expand(args(&["app_name", &at_file]), &|_| false).unwrap_err();
+
+        // then
+        assert_eq!(error.to_string(), format!("{}:2: Unterminated double quote", file.display()));
+    }
+
+    #[test]
+    fn should_report_missing_files() {
+        // when
+        let error = expand(args(&["app_name", "@/nonexistent/args.txt"]), &|_| false).unwrap_err();
+
+        // then
+        assert!(matches!(error, Error::ResponseFile { line: None, .. }));
+    }
+
+    #[test]
+    fn should_keep_values_of_literal_options() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("args.txt"), "-k @key.txt").unwrap();
+        let at_file = format!("@{}", dir.path().join("args.txt").display());
+        let literal = |arg: &OsStr| arg == "-k";
+
+        // when
+        let expanded = words(expand(args(&["app_name", &at_file, "-k", "@secret.txt"]), &literal).unwrap());
+
+        // then
+        assert_eq!(expanded, args(&["app_name", "-k", "@key.txt", "-k", "@secret.txt"]));
+    }
+
+    #[test]
+    fn should_record_where_words_came_from() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.txt");
+        fs::write(&file, "-a\n-b c").unwrap();
+        let at_file = format!("@{}", file.display());
+
+        // when
+        let expanded = expand(args(&["app_name", &at_file, "-d"]), &|_| false).unwrap();
+
+        // then
+        let sources: Vec<ArgSource> = expanded.into_iter().map(|(_, source)| source).collect();
+        assert_eq!(sources, vec![
+            ArgSource { index: 0, file: None },
+            ArgSource { index: 1, file: Some((file.clone(), 1)) },
+            ArgSource { index: 1, file: Some((file.clone(), 2)) },
+            ArgSource { index: 1, file: Some((file.clone(), 2)) },
+            ArgSource { index: 2, file: None },
+        ]);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SplitError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}", self.message, self.line)
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+// Splits a string into words following POSIX shell quoting rules: single
+// quotes are literal, double quotes allow escaping `\`, `"`, `$`, `` ` `` and
+// newlines, and a backslash outside quotes escapes any character.
+pub fn split(input: &str) -> Result<Vec<String>, SplitError> {
+    Ok(split_lines(input)?.into_iter().map(|(word, _)| word).collect())
+}
+
+// Like `split`, along with the line each word starts on.
+pub(crate) fn split_lines(input: &str) -> Result<Vec<(String, usize)>, SplitError> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut word_line = 1;
+    let mut line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let (starts_word, start_line) = (word.is_none(), line);
+        match c {
+            '\n' => {
+                line += 1;
+                words.extend(word.take().map(|word| (word, word_line)));
+            },
+            c if c.is_whitespace() => words.extend(word.take().map(|word| (word, word_line))),
+            '#' if word.is_none() => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            },
+            '\\' => match chars.next() {
+                Some('\n') => line += 1,
+                Some(escaped) => word.get_or_insert_with(String::new).push(escaped),
+                None => return Err(SplitError {
+                    line,
+                    message: "Unexpected end of input after backslash".to_string(),
+                }),
+            },
+            '\'' => {
+                let start = line;
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            word.push(c);
+                        },
+                        None => return Err(SplitError {
+                            line: start,
+                            message: "Unterminated single quote".to_string(),
+                        }),
+                    }
+                }
+            },
+            '"' => {
+                let start = line;
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next_if(|c| matches!(c, '\\' | '"' | '$' | '`' | '\n')) {
+                            Some('\n') => line += 1,
+                            Some(escaped) => word.push(escaped),
+                            None => word.push('\\'),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            word.push(c);
+                        },
+                        None => return Err(SplitError {
+                            line: start,
+                            message: "Unterminated double quote".to_string(),
+                        }),
+                    }
+                }
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+        if starts_word && word.is_some() {
+            word_line = start_line;
+        }
+    }
+    words.extend(word.map(|word| (word, word_line)));
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_on_whitespace() {
+        assert_eq!(split("  -s  foo\t-b\n-i 42 ").unwrap(), vec!["-s", "foo", "-b", "-i", "42"]);
+    }
+
+    #[test]
+    fn should_keep_quoted_strings_together() {
+        assert_eq!(split(r#"-s 'a b' -t "c d" e"f"'g'"#).unwrap(), vec!["-s", "a b", "-t", "c d", "efg"]);
+    }
+
+    #[test]
+    fn should_keep_empty_quoted_strings() {
+        assert_eq!(split(r#"-s "" ''"#).unwrap(), vec!["-s", "", ""]);
+    }
+
+    #[test]
+    fn should_handle_backslash_escapes() {
+        assert_eq!(split(r"a\ b c\\d \'e").unwrap(), vec!["a b", r"c\d", "'e"]);
+        assert_eq!(split(r#""a\"b\n" 'c\d'"#).unwrap(), vec![r#"a"b\n"#, r"c\d"]);
+        assert_eq!(split("a\\\nb").unwrap(), vec!["ab"]);
+    }
+
+    #[test]
+    fn should_skip_comments() {
+        assert_eq!(split("-b # comment\n-s a#b").unwrap(), vec!["-b", "-s", "a#b"]);
+    }
+
+    #[test]
+    fn should_give_line_each_word_starts_on() {
+        assert_eq!(split_lines("-a\n# c\n-s 'b\nc' d").unwrap(), vec![
+            ("-a".to_string(), 1),
+            ("-s".to_string(), 3),
+            ("b\nc".to_string(), 3),
+            ("d".to_string(), 4),
+        ]);
+    }
+
+    #[test]
+    fn should_report_unterminated_quotes() {
+        assert_eq!(split("-s foo\n-t 'bar\nbaz").unwrap_err(), SplitError {
+            line: 2,
+            message: "Unterminated single quote".to_string(),
+        });
+        assert_eq!(split("\"foo").unwrap_err().message, "Unterminated double quote");
+        assert_eq!(split("foo\\").unwrap_err().message, "Unexpected end of input after backslash");
+    }
+}
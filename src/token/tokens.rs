@@ -1,9 +1,14 @@
-use crate::token::Token;
+use crate::token::{Span, Token};
 use crate::schema::Schema;
+use crate::sensitive::Sensitive;
+use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Default)]
 pub struct Tokens {
     items: Vec<Token>,
+    spans: Vec<Option<Span>>,
+    spellings: HashMap<usize, String>,
     schema: Option<Schema>,
     index: usize,
 }
@@ -13,12 +18,34 @@ impl Tokens {
         Self {
             schema: Some(schema),
             items: Vec::new(),
+            spans: Vec::new(),
+            spellings: HashMap::new(),
             index: 0,
         }
     }
 
     pub fn add(&mut self, token: Token) {
         self.items.push(token);
+        self.spans.push(None);
+    }
+
+    pub fn add_spanned(&mut self, token: Token, span: Span) {
+        self.items.push(token);
+        self.spans.push(Some(span));
+    }
+
+    // Remembers how the user typed the option of the last token, which may
+    // be an alias or an abbreviation of its name.
+    pub(crate) fn set_last_spelling(&mut self, spelling: &str) {
+        if let Some(index) = self.items.len().checked_sub(1) {
+            self.spellings.insert(index, spelling.to_string());
+        }
+    }
+
+    pub(crate) fn mark_last_sensitive(&mut self) {
+        if let Some(token) = self.items.pop() {
+            self.items.push(Token::Sensitive(Sensitive::new(Box::new(token))));
+        }
     }
 
     pub fn size(&self) -> usize {
@@ -33,6 +60,10 @@ impl Tokens {
         self.schema.as_ref()
     }
 
+    pub fn schema_take(&mut self) -> Option<Schema> {
+        self.schema.take()
+    }
+
     pub fn schema_set(&mut self, schema: Schema) {
         self.schema = Some(schema);
     }
@@ -41,11 +72,25 @@ impl Tokens {
         self.items.get(self.index)
     }
 
+    pub fn current_span(&self) -> Option<&Span> {
+        self.spans.get(self.index)?.as_ref()
+    }
+
+    pub fn current_spelling(&self) -> Option<&str> {
+        self.spellings.get(&self.index).map(String::as_str)
+    }
+
     pub fn next(&mut self) {
         self.index += 1;
     }
 }
 
+impl fmt::Debug for Tokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.items).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,4 +135,21 @@ mod tests {
         // then
         assert_eq!(current, Some(Token::Argument(ArgumentType::Bool, "b".to_string())).as_ref());
     }
+
+    #[test]
+    fn should_get_span_of_current_token() {
+        // given
+        let mut tokens = Tokens::default();
+        tokens.add(Token::AppName);
+        tokens.add_spanned(Token::StrValue("foo".to_string()), Span { index: 2, range: 6..9, file: None });
+
+        // when
+        let first = tokens.current_span().cloned();
+        tokens.next();
+        let second = tokens.current_span().cloned();
+
+        // then
+        assert_eq!(first, None);
+        assert_eq!(second, Some(Span { index: 2, range: 6..9, file: None }));
+    }
 }